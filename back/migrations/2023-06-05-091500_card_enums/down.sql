@@ -0,0 +1,6 @@
+ALTER TABLE data
+	ALTER COLUMN status TYPE varchar USING status::text,
+	ALTER COLUMN priority TYPE varchar USING priority::text;
+
+DROP TYPE card_priority;
+DROP TYPE card_status;
@@ -0,0 +1,11 @@
+CREATE TYPE card_status AS ENUM ('todo', 'inprogress', 'completed');
+CREATE TYPE card_priority AS ENUM ('low', 'medium', 'high');
+
+-- Cards written before the enums existed may hold values no column renders,
+-- move them back onto the defaults so the conversion below cannot fail.
+UPDATE data SET status = 'todo' WHERE status NOT IN ('todo', 'inprogress', 'completed');
+UPDATE data SET priority = 'medium' WHERE priority NOT IN ('low', 'medium', 'high');
+
+ALTER TABLE data
+	ALTER COLUMN status TYPE card_status USING status::card_status,
+	ALTER COLUMN priority TYPE card_priority USING priority::card_priority;
@@ -0,0 +1,7 @@
+ALTER TABLE data
+	ADD COLUMN created_at timestamptz NOT NULL DEFAULT NOW(),
+	ADD COLUMN updated_at timestamptz NOT NULL DEFAULT NOW();
+
+SELECT diesel_manage_updated_at('data');
+
+CREATE INDEX data_updated_at_idx ON data (updated_at);
@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS bump_version ON data;
+DROP FUNCTION IF EXISTS card_bump_version();
+
+ALTER TABLE data DROP COLUMN version;
@@ -0,0 +1,15 @@
+ALTER TABLE data ADD COLUMN version integer NOT NULL DEFAULT 1;
+
+-- Bumps the version of a card whenever one of its columns actually changes,
+-- the API compares it against the If-Match header before writing
+CREATE OR REPLACE FUNCTION card_bump_version() RETURNS trigger AS $$
+BEGIN
+    IF NEW IS DISTINCT FROM OLD THEN
+        NEW.version := OLD.version + 1;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER bump_version BEFORE UPDATE ON data
+	FOR EACH ROW EXECUTE PROCEDURE card_bump_version();
@@ -0,0 +1,3 @@
+DELETE FROM data WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE data DROP COLUMN deleted_at;
@@ -0,0 +1,3 @@
+ALTER TABLE data ADD COLUMN deleted_at timestamptz;
+
+CREATE INDEX data_deleted_at_idx ON data (deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,14 @@
+CREATE TABLE card_revisions (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	version integer NOT NULL,
+	action varchar NOT NULL,
+	author varchar,
+	snapshot jsonb NOT NULL,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	UNIQUE (card_id, version)
+);
+
+-- Existing cards start their history with their current state
+INSERT INTO card_revisions (card_id, version, action, snapshot, created_at)
+	SELECT id, version, 'create', to_jsonb(data), updated_at FROM data;
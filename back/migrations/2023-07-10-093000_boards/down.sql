@@ -0,0 +1,3 @@
+ALTER TABLE data DROP COLUMN board_id;
+
+DROP TABLE boards;
@@ -0,0 +1,17 @@
+CREATE TABLE boards (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	name varchar NOT NULL UNIQUE,
+	description varchar NOT NULL DEFAULT '',
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	updated_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+SELECT diesel_manage_updated_at('boards');
+
+-- Cards created before boards existed, and the routes without a board, use this one
+INSERT INTO boards (id, name) VALUES (1, 'Default');
+SELECT setval('boards_id_seq', 1);
+
+ALTER TABLE data ADD COLUMN board_id bigint NOT NULL DEFAULT 1 REFERENCES boards (id);
+
+CREATE INDEX data_board_id_idx ON data (board_id);
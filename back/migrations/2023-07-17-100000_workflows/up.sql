@@ -0,0 +1,26 @@
+CREATE TABLE workflow_columns (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	board_id bigint NOT NULL REFERENCES boards (id) ON DELETE CASCADE,
+	status card_status NOT NULL,
+	name varchar NOT NULL,
+	color varchar NOT NULL,
+	position integer NOT NULL,
+	UNIQUE (board_id, status)
+);
+
+-- A board without any transition lets cards move freely between its columns
+CREATE TABLE workflow_transitions (
+	board_id bigint NOT NULL REFERENCES boards (id) ON DELETE CASCADE,
+	from_status card_status NOT NULL,
+	to_status card_status NOT NULL,
+	PRIMARY KEY (board_id, from_status, to_status)
+);
+
+-- Existing boards get the three columns the client always displayed
+INSERT INTO workflow_columns (board_id, status, name, color, position)
+	SELECT boards.id, columns.status, columns.name, columns.color, columns.position
+	FROM boards CROSS JOIN (VALUES
+		('todo'::card_status, 'To-do', '#FFEB3B', 0),
+		('inprogress'::card_status, 'In Progress', '#2196F3', 1),
+		('completed'::card_status, 'Completed', '#4CAF50', 2)
+	) AS columns (status, name, color, position);
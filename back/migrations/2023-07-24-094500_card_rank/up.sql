@@ -0,0 +1,14 @@
+-- Position of a card inside its column, ranks are compared byte by byte
+ALTER TABLE data ADD COLUMN rank varchar COLLATE "C" NOT NULL DEFAULT '';
+
+-- Existing cards keep their creation order, ranks never end with the lowest digit
+UPDATE data SET rank = ranked.rank
+FROM (
+	SELECT id, lpad(row_number() OVER (PARTITION BY board_id, status ORDER BY id)::text, 10, '0') || 'i' AS rank
+	FROM data
+) AS ranked
+WHERE data.id = ranked.id;
+
+ALTER TABLE data ALTER COLUMN rank DROP DEFAULT;
+
+CREATE INDEX data_board_status_rank_idx ON data (board_id, status, rank);
@@ -0,0 +1,3 @@
+DROP TABLE card_labels;
+
+DROP TABLE labels;
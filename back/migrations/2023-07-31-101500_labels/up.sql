@@ -0,0 +1,16 @@
+CREATE TABLE labels (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	board_id bigint NOT NULL REFERENCES boards (id) ON DELETE CASCADE,
+	name varchar NOT NULL,
+	color varchar NOT NULL,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	UNIQUE (board_id, name)
+);
+
+CREATE TABLE card_labels (
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	label_id bigint NOT NULL REFERENCES labels (id) ON DELETE CASCADE,
+	PRIMARY KEY (card_id, label_id)
+);
+
+CREATE INDEX card_labels_label_id_idx ON card_labels (label_id);
@@ -0,0 +1,13 @@
+CREATE TABLE checklist_items (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	text varchar NOT NULL,
+	done boolean NOT NULL DEFAULT false,
+	position integer NOT NULL,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	updated_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+SELECT diesel_manage_updated_at('checklist_items');
+
+CREATE INDEX checklist_items_card_id_idx ON checklist_items (card_id, position);
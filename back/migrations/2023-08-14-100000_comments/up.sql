@@ -0,0 +1,13 @@
+-- Comments of a trashed card are hidden with it, and purged along with it
+CREATE TABLE comments (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	author varchar,
+	body varchar NOT NULL,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	updated_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+SELECT diesel_manage_updated_at('comments');
+
+CREATE INDEX comments_card_id_idx ON comments (card_id, created_at);
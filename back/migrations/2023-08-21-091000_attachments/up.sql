@@ -0,0 +1,12 @@
+-- Files live in the attachment storage directory, named after the attachment id
+CREATE TABLE attachments (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	filename varchar NOT NULL,
+	size bigint NOT NULL,
+	mime varchar NOT NULL,
+	sha256 varchar NOT NULL,
+	created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX attachments_card_id_idx ON attachments (card_id);
@@ -0,0 +1,3 @@
+DROP TABLE card_assignees;
+
+DROP TABLE users;
@@ -0,0 +1,13 @@
+CREATE TABLE users (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	name varchar NOT NULL UNIQUE,
+	created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE card_assignees (
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	user_id bigint NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	PRIMARY KEY (card_id, user_id)
+);
+
+CREATE INDEX card_assignees_user_id_idx ON card_assignees (user_id);
@@ -0,0 +1,9 @@
+CREATE TABLE card_dependencies (
+	blocker_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	blocked_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	PRIMARY KEY (blocker_id, blocked_id),
+	CHECK (blocker_id <> blocked_id)
+);
+
+CREATE INDEX card_dependencies_blocked_id_idx ON card_dependencies (blocked_id);
@@ -0,0 +1,3 @@
+ALTER TABLE data DROP COLUMN recurrence_id;
+
+DROP TABLE recurrences;
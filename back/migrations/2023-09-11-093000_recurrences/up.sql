@@ -0,0 +1,15 @@
+CREATE TABLE recurrences (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	card_id bigint NOT NULL UNIQUE REFERENCES data (id) ON DELETE CASCADE,
+	rule varchar NOT NULL,
+	generated_until date,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	updated_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+SELECT diesel_manage_updated_at('recurrences');
+
+ALTER TABLE data ADD COLUMN recurrence_id bigint REFERENCES recurrences (id) ON DELETE SET NULL;
+
+-- An occurrence is materialised once, whatever happens to it afterwards
+CREATE UNIQUE INDEX data_recurrence_id_date_idx ON data (recurrence_id, date);
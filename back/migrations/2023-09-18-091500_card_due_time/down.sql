@@ -0,0 +1,3 @@
+ALTER TABLE data
+	DROP COLUMN due_at,
+	DROP COLUMN time_zone;
@@ -0,0 +1,6 @@
+-- Cards without a due time keep a floating `date`, the same day in every time zone
+ALTER TABLE data
+	ADD COLUMN due_at timestamptz,
+	ADD COLUMN time_zone varchar;
+
+CREATE INDEX data_due_at_idx ON data (due_at);
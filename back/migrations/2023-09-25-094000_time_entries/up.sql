@@ -0,0 +1,15 @@
+CREATE TABLE time_entries (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	card_id bigint NOT NULL REFERENCES data (id) ON DELETE CASCADE,
+	user_id bigint NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+	started_at timestamptz NOT NULL DEFAULT NOW(),
+	ended_at timestamptz,
+	note varchar NOT NULL DEFAULT '',
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	CHECK (ended_at IS NULL OR ended_at >= started_at)
+);
+
+CREATE INDEX time_entries_card_id_idx ON time_entries (card_id);
+
+-- A user tracks one card at a time
+CREATE UNIQUE INDEX time_entries_running_idx ON time_entries (user_id) WHERE ended_at IS NULL;
@@ -0,0 +1,10 @@
+CREATE TABLE card_templates (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	board_id bigint NOT NULL REFERENCES boards (id) ON DELETE CASCADE,
+	name varchar NOT NULL,
+	card jsonb NOT NULL,
+	checklist text[] NOT NULL DEFAULT '{}',
+	label_ids bigint[] NOT NULL DEFAULT '{}',
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	UNIQUE (board_id, name)
+);
@@ -0,0 +1,6 @@
+-- Cards break down into child cards, such as an epic and its stories
+ALTER TABLE data
+	ADD COLUMN parent_id bigint REFERENCES data (id) ON DELETE SET NULL,
+	ADD CHECK (parent_id <> id);
+
+CREATE INDEX data_parent_id_idx ON data (parent_id);
@@ -0,0 +1,5 @@
+ALTER TABLE data DROP COLUMN custom;
+
+DROP TABLE custom_fields;
+
+DROP TYPE custom_field_type;
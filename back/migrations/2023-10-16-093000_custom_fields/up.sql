@@ -0,0 +1,17 @@
+CREATE TYPE custom_field_type AS ENUM ('text', 'number', 'date', 'select', 'url');
+
+CREATE TABLE custom_fields (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+	board_id bigint NOT NULL REFERENCES boards (id) ON DELETE CASCADE,
+	name varchar NOT NULL,
+	field_type custom_field_type NOT NULL,
+	options text[] NOT NULL DEFAULT '{}',
+	required boolean NOT NULL DEFAULT false,
+	created_at timestamptz NOT NULL DEFAULT NOW(),
+	UNIQUE (board_id, name)
+);
+
+-- Values of the custom fields of the board, keyed by field name
+ALTER TABLE data ADD COLUMN custom jsonb NOT NULL DEFAULT '{}';
+
+CREATE INDEX data_custom_idx ON data USING GIN (custom jsonb_path_ops);
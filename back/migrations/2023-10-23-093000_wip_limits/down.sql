@@ -0,0 +1,3 @@
+ALTER TABLE workflow_columns
+	DROP COLUMN assignee_wip_limit,
+	DROP COLUMN wip_limit;
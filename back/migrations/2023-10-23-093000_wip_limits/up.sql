@@ -0,0 +1,4 @@
+-- Work in progress limits of a column, for all its cards and for the cards of each assignee
+ALTER TABLE workflow_columns
+	ADD COLUMN wip_limit integer CHECK (wip_limit > 0),
+	ADD COLUMN assignee_wip_limit integer CHECK (assignee_wip_limit > 0);
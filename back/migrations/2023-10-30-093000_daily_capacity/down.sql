@@ -0,0 +1,7 @@
+ALTER TABLE users DROP COLUMN daily_capacity;
+
+ALTER TABLE boards
+	DROP COLUMN capacity_policy,
+	DROP COLUMN daily_capacity;
+
+DROP TYPE capacity_policy;
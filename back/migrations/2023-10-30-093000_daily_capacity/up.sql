@@ -0,0 +1,8 @@
+CREATE TYPE capacity_policy AS ENUM ('warn', 'reject');
+
+-- Hours a board plans and a user works per day, the server defaults apply when NULL
+ALTER TABLE boards
+	ADD COLUMN daily_capacity integer CHECK (daily_capacity > 0),
+	ADD COLUMN capacity_policy capacity_policy;
+
+ALTER TABLE users ADD COLUMN daily_capacity integer CHECK (daily_capacity > 0);
@@ -0,0 +1,2 @@
+-- A stopped recurrence keeps its row, and so its generated dates, a rule set again resumes after them
+ALTER TABLE recurrences ADD COLUMN deleted_at timestamptz;
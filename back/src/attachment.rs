@@ -0,0 +1,405 @@
+use futures_util::TryStreamExt;
+use sha2::{Digest, Sha256};
+use std::{
+    cell::RefCell,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+use warp::{
+    http::header,
+    hyper::{body::Buf, Body},
+    multipart::{FormData, Part},
+    Reply,
+};
+
+use crate::{
+    card::respond,
+    database::{DBAccessManager, PgPool},
+    domain::{Attachment, NewAttachment},
+    errors::{AppError, ErrorType},
+    storage::Storage,
+};
+
+fn io_err(context: &str, err: std::io::Error) -> AppError {
+    AppError::new(
+        format!("{}: {}", context, err).as_str(),
+        ErrorType::Internal,
+    )
+}
+
+/// Stream a file part to disk, hashing it on the way
+/// # Returns
+/// * `(i64, String)` - Size of the file and its hex encoded sha256
+///
+async fn write_part(part: Part, path: &Path, max_size: u64) -> Result<(i64, String), AppError> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|err| io_err("while creating upload", err))?;
+    let mut hasher = Sha256::new();
+    let mut size: u64 = 0;
+    let mut stream = part.stream();
+
+    while let Some(mut chunk) = stream.try_next().await.map_err(|err| {
+        AppError::new(
+            format!("while reading upload: {}", err).as_str(),
+            ErrorType::BadRequest,
+        )
+    })? {
+        while chunk.has_remaining() {
+            let bytes = chunk.chunk();
+            size += bytes.len() as u64;
+            if size > max_size {
+                return Err(AppError::new(
+                    format!("files are limited to {} bytes", max_size).as_str(),
+                    ErrorType::PayloadTooLarge,
+                ));
+            }
+
+            hasher.update(bytes);
+            file.write_all(bytes)
+                .await
+                .map_err(|err| io_err("while writing upload", err))?;
+
+            let read = bytes.len();
+            chunk.advance(read);
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|err| io_err("while writing upload", err))?;
+
+    Ok((size as i64, format!("{:x}", hasher.finalize())))
+}
+
+/// Write every file of a multipart form to the storage, before they are recorded
+/// No database connection is held meanwhile, slow uploads would otherwise drain the pool
+async fn receive_form(
+    storage: &Storage,
+    mut form: FormData,
+    uploads: &mut Vec<(PathBuf, NewAttachment)>,
+    _card_id: i64,
+) -> Result<(), AppError> {
+    while let Some(part) = form.try_next().await.map_err(|err| {
+        AppError::new(
+            format!("while reading upload: {}", err).as_str(),
+            ErrorType::BadRequest,
+        )
+    })? {
+        // Only the file name is kept, clients may send a full path
+        let filename = match part.filename().and_then(|name| {
+            Path::new(&name.replace('\\', "/"))
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        }) {
+            Some(filename) => filename,
+            None => continue,
+        };
+        // Warp only exposes the top level type of a part, the extension is more precise
+        let mime = mime_guess::from_path(&filename)
+            .first_or_octet_stream()
+            .to_string();
+
+        let path = storage.upload_path();
+        uploads.push((
+            path.clone(),
+            NewAttachment {
+                card_id: _card_id,
+                filename,
+                size: 0,
+                mime,
+                sha256: String::new(),
+            },
+        ));
+
+        let (size, sha256) = write_part(part, &path, storage.max_size).await?;
+        if let Some((_, upload)) = uploads.last_mut() {
+            upload.size = size;
+            upload.sha256 = sha256;
+        }
+    }
+
+    if uploads.is_empty() {
+        return Err(AppError::new(
+            "the upload does not contain any file",
+            ErrorType::BadRequest,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Upload files to a card.
+/// Swagger config:
+///   - Operation ID: upload_attachments
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/attachments
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: multipart/form-data, every part with a filename is stored
+///   - Response: JSON list of the created attachments
+///   - Error 413: the upload is larger than ATTACHMENT_MAX_SIZE
+pub async fn upload_attachments(
+    _board_id: i64,
+    _id: i64,
+    _pool: PgPool,
+    _storage: Storage,
+    _form: FormData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    // Fails early instead of streaming files for a missing card, the connection goes back right away
+    if let Err(err) = DBAccessManager::from_pool(&_pool)
+        .and_then(|db_manager| db_manager.get_card_by_id(_board_id, _id))
+    {
+        let status = err.to_status_code();
+        return respond::<Vec<Attachment>>(Err(err), status);
+    }
+
+    let mut uploads = Vec::new();
+    let renamed = RefCell::new(Vec::new());
+    let stored = receive_form(&_storage, _form, &mut uploads, _id)
+        .await
+        .and_then(|()| DBAccessManager::from_pool(&_pool))
+        .and_then(|db_manager| {
+            let rows = uploads.iter().map(|(_, upload)| upload.clone()).collect();
+            db_manager.create_attachments(_board_id, _id, rows, |index, attachment| {
+                std::fs::rename(&uploads[index].0, _storage.path(attachment.id))?;
+                renamed.borrow_mut().push(attachment.id);
+                Ok(())
+            })
+        });
+
+    // Left over when the upload failed, renamed otherwise
+    for (path, _) in uploads {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+    // Files moved in place before a later one failed belong to no attachment
+    if stored.is_err() {
+        _storage.remove(&renamed.into_inner()).await;
+    }
+
+    match stored {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get the attachments of a card.
+/// Swagger config:
+///   - Operation ID: get_attachments
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/attachments
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON list of the attachments metadata
+pub async fn get_attachments(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_attachments(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Parse a `Range` header against a file size, only single byte ranges are supported
+/// # Returns
+/// * `None` - The whole file is sent, the header is absent or not supported
+/// * `Some((start, end))` - Inclusive bounds of the requested bytes
+/// * `AppError` - RangeNotSatisfiable when the range is outside of the file
+///
+fn parse_range(range: Option<&str>, size: u64) -> Result<Option<(u64, u64)>, AppError> {
+    let spec = match range.and_then(|range| range.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return Ok(None),
+    };
+    let (start, end) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return Ok(None),
+    };
+
+    let bounds = match (start.parse::<u64>(), end.parse::<u64>()) {
+        (Ok(start), Ok(end)) if start <= end => Some((start, end.min(size.saturating_sub(1)))),
+        (Ok(start), Err(_)) if end.is_empty() => Some((start, size.saturating_sub(1))),
+        // Suffix range, the last bytes of the file
+        (Err(_), Ok(suffix)) if start.is_empty() && suffix > 0 => {
+            Some((size.saturating_sub(suffix), size.saturating_sub(1)))
+        }
+        _ => return Ok(None),
+    };
+
+    match bounds {
+        Some((start, _)) if start >= size => Err(AppError::new(
+            format!("range is outside of the {} bytes of the file", size).as_str(),
+            ErrorType::RangeNotSatisfiable,
+        )),
+        bounds => Ok(bounds),
+    }
+}
+
+/// Stream the bytes of an attachment, or the requested range of them
+async fn read_attachment(
+    attachment: Attachment,
+    storage: &Storage,
+    range: Option<&str>,
+) -> Result<warp::reply::Response, AppError> {
+    let size = attachment.size as u64;
+    let bounds = parse_range(range, size)?;
+    let (start, end) = bounds.unwrap_or((0, size.saturating_sub(1)));
+    let length = if size == 0 { 0 } else { end - start + 1 };
+
+    let mut file = tokio::fs::File::open(storage.path(attachment.id))
+        .await
+        .map_err(|err| io_err("while opening attachment", err))?;
+    file.seek(SeekFrom::Start(start))
+        .await
+        .map_err(|err| io_err("while reading attachment", err))?;
+
+    let mut response = warp::http::Response::builder()
+        .header(header::CONTENT_TYPE, attachment.mime.as_str())
+        .header(header::CONTENT_LENGTH, length)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ETAG, format!("\"{}\"", attachment.sha256))
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                attachment.filename.replace(['"', '\\'], "_")
+            ),
+        );
+
+    if bounds.is_some() {
+        response = response
+            .status(warp::http::StatusCode::PARTIAL_CONTENT)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, size),
+            );
+    }
+
+    response
+        .body(Body::wrap_stream(ReaderStream::new(file.take(length))))
+        .map_err(|err| {
+            AppError::new(
+                format!("while building response: {}", err).as_str(),
+                ErrorType::Internal,
+            )
+        })
+}
+
+/// Download an attachment.
+/// Swagger config:
+///   - Operation ID: download_attachment
+///   - HTTP Method: GET
+///   - Path: /attachments/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Header: Range (single byte range, optional)
+///   - Response: content of the file, 206 with Content-Range for a range
+///   - Error 416: the range is outside of the file
+pub async fn download_attachment(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _storage: Storage,
+    _range: Option<String>,
+) -> std::result::Result<warp::reply::Response, warp::Rejection> {
+    let read = match _db_manager.get_attachment(_board_id, _id) {
+        Ok(attachment) => read_attachment(attachment, &_storage, _range.as_deref()).await,
+        Err(err) => Err(err),
+    };
+
+    match read {
+        Ok(response) => Ok(response),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond::<()>(Err(err), status).map(Reply::into_response)
+        }
+    }
+}
+
+/// Delete an attachment and its file.
+/// Swagger config:
+///   - Operation ID: delete_attachment
+///   - HTTP Method: DELETE
+///   - Path: /attachments/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted attachments
+pub async fn delete_attachment(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _storage: Storage,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_attachment(_board_id, _id) {
+        Ok(_branch) => {
+            _storage.remove(&[_branch.id]).await;
+            respond(Ok(1), warp::http::StatusCode::ACCEPTED)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_or_unsupported_ranges_send_the_whole_file() {
+        assert_eq!(parse_range(None, 100).unwrap(), None);
+        assert_eq!(parse_range(Some("items=0-10"), 100).unwrap(), None);
+        assert_eq!(parse_range(Some("bytes=0-10,20-30"), 100).unwrap(), None);
+        assert_eq!(parse_range(Some("bytes=10"), 100).unwrap(), None);
+        assert_eq!(parse_range(Some("bytes=-"), 100).unwrap(), None);
+        assert_eq!(parse_range(Some("bytes=-0"), 100).unwrap(), None);
+    }
+
+    #[test]
+    fn start_after_end_is_ignored() {
+        assert_eq!(parse_range(Some("bytes=50-10"), 100).unwrap(), None);
+    }
+
+    #[test]
+    fn bounded_ranges_stop_at_the_last_byte() {
+        assert_eq!(parse_range(Some("bytes=0-9"), 100).unwrap(), Some((0, 9)));
+        assert_eq!(
+            parse_range(Some(" bytes=90-500 "), 100).unwrap(),
+            Some((90, 99))
+        );
+        assert_eq!(parse_range(Some("bytes=40-"), 100).unwrap(), Some((40, 99)));
+    }
+
+    #[test]
+    fn suffix_ranges_read_the_last_bytes() {
+        assert_eq!(parse_range(Some("bytes=-10"), 100).unwrap(), Some((90, 99)));
+        assert_eq!(parse_range(Some("bytes=-500"), 100).unwrap(), Some((0, 99)));
+    }
+
+    #[test]
+    fn ranges_starting_past_the_file_are_not_satisfiable() {
+        let cases = [
+            ("bytes=100-", 100),
+            ("bytes=100-200", 100),
+            // Nothing can be read from an empty file
+            ("bytes=0-", 0),
+            ("bytes=-10", 0),
+        ];
+
+        for (range, size) in cases {
+            let err = parse_range(Some(range), size).unwrap_err();
+            assert!(matches!(err.err_type, ErrorType::RangeNotSatisfiable));
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    card::respond,
+    database::DBAccessManager,
+    domain::{Board, BoardData},
+    validation::Validate,
+};
+
+/// Create a new board.
+/// Swagger config:
+///   - Operation ID: create_board
+///   - HTTP Method: POST
+///   - Path: /boards
+///   - Request Body: BoardData
+///   - Response: JSON representation of the created board
+///   - Error 409: a board with this name already exists
+///   - Error 422: list of the fields that failed validation
+pub async fn create_board(
+    _db_manager: DBAccessManager,
+    _new_data: BoardData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Board>(Err(err), status);
+    }
+
+    match _db_manager.create_board(_new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get all boards.
+/// Swagger config:
+///   - Operation ID: get_all_boards
+///   - HTTP Method: GET
+///   - Path: /boards
+///   - Response: JSON representation of all boards
+pub async fn get_all_boards(
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_all_boards() {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get a board by ID.
+/// Swagger config:
+///   - Operation ID: get_board_by_id
+///   - HTTP Method: GET
+///   - Path: /boards/{id}
+///   - Path Parameter: id (integer)
+///   - Response: JSON representation of the board with the specified ID
+pub async fn get_board_by_id(
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_board(_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit an existing board.
+/// Swagger config:
+///   - Operation ID: edit_board
+///   - HTTP Method: PUT
+///   - Path: /boards/{id}
+///   - Path Parameter: id (integer)
+///   - Request Body: BoardData
+///   - Response: JSON representation of the edited board
+///   - Error 409: a board with this name already exists
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_board(
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: BoardData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Board>(Err(err), status);
+    }
+
+    match _db_manager.edit_board(_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete an empty board.
+/// Swagger config:
+///   - Operation ID: delete_board
+///   - HTTP Method: DELETE
+///   - Path: /boards/{id}
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted boards
+///   - Error 409: the board still holds cards
+pub async fn delete_board(
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_board(_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
@@ -1,10 +1,17 @@
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 use serde::Serialize;
+use warp::Reply;
 
 use crate::{
     database::DBAccessManager,
-    domain::CardData,
+    domain::{
+        CapacityChecked, CapacityQuery, Card, CardData, CardDeleteQuery, CardListQuery, CardMove,
+        CardPatch, CardView, DailyCapacity, DateRangeQuery, EffortReport, PlannedDuration,
+        Versioned, Workload, WorkloadQuery,
+    },
     errors::{AppError, ErrorType},
+    validation::{check_date, check_fields, Validate},
 };
 
 pub fn respond<T: Serialize>(
@@ -23,23 +30,111 @@ pub fn respond<T: Serialize>(
     }
 }
 
+/// Respond with a card, exposing its version as the `ETag` header.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn respond_card<T: Serialize + Versioned>(
+    result: Result<T, AppError>,
+    status: warp::http::StatusCode,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    match result {
+        Ok(card) => {
+            let etag = card.etag();
+            Ok(warp::reply::with_header(
+                warp::reply::with_status(warp::reply::json(&card), status),
+                "ETag",
+                etag,
+            )
+            .into_response())
+        }
+        Err(err) => respond::<T>(Err(err), status).map(Reply::into_response),
+    }
+}
+
+/// Respond with a written card and the daily capacities it goes over, in its `capacity_warnings`
+/// and as `X-Capacity-Warning` headers, percent-encoded as header values are ASCII.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn respond_checked<T: Serialize + Versioned>(
+    card: T,
+    warnings: Vec<String>,
+    status: warp::http::StatusCode,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let headers: Vec<String> = warnings
+        .iter()
+        .map(|warning| percent_encode(warning))
+        .collect();
+    let checked = CapacityChecked {
+        card,
+        capacity_warnings: warnings,
+    };
+
+    respond_card(Ok(checked), status).map(|mut response| {
+        for header in headers {
+            if let Ok(value) = warp::http::HeaderValue::from_str(&header) {
+                response.headers_mut().append("X-Capacity-Warning", value);
+            }
+        }
+        response
+    })
+}
+
+/// Percent-encode the bytes of a text that are not printable ASCII, `%` included
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b' '..=b'~' if byte != b'%' => char::from(byte).to_string(),
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Daily capacities the written card goes over, the card is saved whatever the outcome.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn capacity_warnings(db_manager: &DBAccessManager, card: &Card) -> Vec<String> {
+    db_manager.capacity_warnings(card).unwrap_or_else(|err| {
+        log::error!("Error while checking the daily capacity: {}", err);
+        Vec::new()
+    })
+}
+
 /// Create a new card.
 /// Swagger config:
 ///   - Operation ID: create_card
 ///   - HTTP Method: POST
 ///   - Path: /cards
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
 ///   - Request Body: CardData
-///   - Response: JSON representation of the created card
+///   - Response: JSON representation of the created card, with its ETag
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day goes over a daily capacity under the reject policy
+///   - Error 422: list of the fields that failed validation
 pub async fn create_card(
+    _board_id: i64,
     _db_manager: DBAccessManager,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
     _new_data: CardData,
 ) -> std::result::Result<impl warp::Reply, warp::Rejection> {
-    match _db_manager.create_card(_new_data) {
-        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
-        Err(_) => respond(
-            Err(AppError::new("Internal server error", ErrorType::Internal)),
-            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-        ),
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Card>(Err(err), status).map(Reply::into_response);
+    }
+
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    match _db_manager.create_card(_board_id, _new_data) {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch);
+            respond_checked(_branch, warnings, warp::http::StatusCode::CREATED)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond::<Card>(Err(err), status).map(Reply::into_response)
+        }
     }
 }
 
@@ -48,20 +143,118 @@ pub async fn create_card(
 ///   - Operation ID: edit_card
 ///   - HTTP Method: PUT
 ///   - Path: /cards/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
 ///   - Path Parameter: id (integer)
+///   - Header: If-Match (card ETag, optional)
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
 ///   - Request Body: CardData
-///   - Response: JSON representation of the edited card
+///   - Response: JSON representation of the edited card, with its ETag
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day goes over a daily capacity under the reject policy
+///   - Error 409: another occurrence of the card's recurrence is already planned on the new date
+///   - Error 412: the card changed since the ETag was read
+///   - Error 422: list of the fields that failed validation
 pub async fn edit_card(
+    _board_id: i64,
     _id: i64,
     _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
     _new_data: CardData,
 ) -> std::result::Result<impl warp::Reply, warp::Rejection> {
-    match _db_manager.edit_card(_id, _new_data) {
-        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
-        Err(_) => respond(
-            Err(AppError::new("Internal server error", ErrorType::Internal)),
-            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-        ),
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond_card::<Card>(Err(err), status);
+    }
+
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    match _db_manager.edit_card(_board_id, _id, _new_data, _if_match) {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch);
+            respond_checked(_branch, warnings, warp::http::StatusCode::OK)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<Card>(Err(err), status)
+        }
+    }
+}
+
+/// Partially update an existing card.
+/// Swagger config:
+///   - Operation ID: patch_card
+///   - HTTP Method: PATCH
+///   - Path: /cards/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Header: If-Match (card ETag, optional)
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
+///   - Request Body: CardPatch (JSON Merge Patch, every field optional)
+///   - Response: JSON representation of the patched card, with its ETag
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day goes over a daily capacity under the reject policy
+///   - Error 409: another occurrence of the card's recurrence is already planned on the new date
+///   - Error 412: the card changed since the ETag was read
+///   - Error 422: list of the fields that failed validation
+pub async fn patch_card(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
+    _patch: CardPatch,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _patch.validate() {
+        let status = err.to_status_code();
+        return respond_card::<Card>(Err(err), status);
+    }
+
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    match _db_manager.patch_card(_board_id, _id, _patch, _if_match) {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch);
+            respond_checked(_branch, warnings, warp::http::StatusCode::OK)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<Card>(Err(err), status)
+        }
+    }
+}
+
+/// Move a card to a position of a column.
+/// Swagger config:
+///   - Operation ID: move_card
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/move
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Header: If-Match (card ETag, optional)
+///   - Request Body: CardMove (target status, optional before/after neighbour ids)
+///   - Response: JSON representation of the moved card, with its ETag
+///   - Error 400: a neighbour is not in the target column, or the transition is not allowed
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 412: the card changed since the ETag was read
+pub async fn move_card(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+    _move: CardMove,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.move_card(_board_id, _id, _move, _if_match) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<Card>(Err(err), status)
+        }
     }
 }
 
@@ -70,16 +263,24 @@ pub async fn edit_card(
 ///   - Operation ID: get_all_cards
 ///   - HTTP Method: GET
 ///   - Path: /cards
-///   - Response: JSON representation of all cards
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Query Parameter: updated_since (RFC 3339 timestamp, optional)
+///   - Query Parameter: sort (rank | id | created_at | updated_at, optional, rank by default)
+///   - Query Parameter: order (asc | desc, optional)
+///   - Query Parameter: label (comma separated label ids, optional)
+///   - Query Parameter: label_match (any | all, optional, any by default)
+///   - Response: JSON representation of all cards, with their labels, checklist progress and comment count
 pub async fn get_all_cards(
+    _board_id: i64,
     _db_manager: DBAccessManager,
+    _query: CardListQuery,
 ) -> std::result::Result<impl warp::Reply, warp::Rejection> {
-    match _db_manager.get_all_cards() {
+    match _db_manager.get_all_cards(_board_id, _query) {
         Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
-        Err(_) => respond(
-            Err(AppError::new("Internal server error", ErrorType::Internal)),
-            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-        ),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
     }
 }
 
@@ -88,39 +289,52 @@ pub async fn get_all_cards(
 ///   - Operation ID: get_card_by_id
 ///   - HTTP Method: GET
 ///   - Path: /cards/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
 ///   - Path Parameter: id (integer)
-///   - Response: JSON representation of the card with the specified ID
+///   - Response: JSON representation of the card with the specified ID, its labels, checklist progress
+///     and comment count, with its ETag
 pub async fn get_card_by_id(
+    _board_id: i64,
     _id: i64,
     _db_manager: DBAccessManager,
 ) -> std::result::Result<impl warp::Reply, warp::Rejection> {
-    match _db_manager.get_card_by_id(_id) {
-        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
-        Err(_) => respond(
+    match _db_manager
+        .get_card_by_id(_board_id, _id)
+        .and_then(|card| _db_manager.card_view(card))
+    {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(_) => respond_card::<CardView>(
             Err(AppError::new("Not found", ErrorType::NotFound)),
             warp::http::StatusCode::NOT_FOUND,
         ),
     }
 }
 
-#[derive(Serialize)]
-struct ReplyTotal {
-    total: i64,
-    date: NaiveDate,
-}
-
-/// Check the total duration on a specific date.
+/// Check the hours planned on a specific date, for each assignee.
 /// Swagger config:
 ///   - Operation ID: check_total_duration_on_date
 ///   - HTTP Method: GET
 ///   - Path: /cards/total_duration
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
 ///   - Query Parameter: date (string in "YYYY-MM-DD" format)
-///   - Response: JSON representation of the total duration on the specified date
+///   - Query Parameter: tz (IANA time zone the day is read in, optional, UTC by default)
+///   - Header: X-Time-Zone (same as tz, the query parameter wins)
+///   - Response: JSON PlannedDuration, the total, the unassigned hours and the hours of each assignee
+///   - Error 422: the date is out of the range cards are planned in
 pub async fn check_total_duration_on_date(
+    _board_id: i64,
     _date: NaiveDate,
     _db_manager: DBAccessManager,
+    _time_zone: Tz,
 ) -> std::result::Result<impl warp::Reply, warp::Rejection> {
-    match _db_manager.check_total_duration_on_date(_date) {
+    let mut errors = Vec::new();
+    check_date(_date, &mut errors);
+    if let Err(err) = check_fields(errors) {
+        let status = err.to_status_code();
+        return respond::<PlannedDuration>(Err(err), status);
+    }
+
+    match _db_manager.check_total_duration_on_date(_board_id, _date, _time_zone) {
         Ok(_total) => respond(Ok(_total), warp::http::StatusCode::OK),
         Err(_) => respond(
             Err(AppError::new("Not found", ErrorType::NotFound)),
@@ -129,22 +343,142 @@ pub async fn check_total_duration_on_date(
     }
 }
 
+/// Compare the estimated and tracked time of the cards planned in a range of days.
+/// Swagger config:
+///   - Operation ID: effort_report
+///   - HTTP Method: GET
+///   - Path: /cards/report
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Query Parameter: from (string in "YYYY-MM-DD" format)
+///   - Query Parameter: to (string in "YYYY-MM-DD" format, included)
+///   - Query Parameter: tz (IANA time zone the days are read in, optional, UTC by default)
+///   - Header: X-Time-Zone (same as tz, the query parameter wins)
+///   - Response: JSON EffortReport, the estimated and tracked minutes of each card and their totals
+///   - Error 422: list of the fields that failed validation
+pub async fn effort_report(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+    _range: DateRangeQuery,
+    _time_zone: Tz,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _range.validate() {
+        let status = err.to_status_code();
+        return respond::<EffortReport>(Err(err), status);
+    }
+
+    match _db_manager.effort_report(_board_id, _range, _time_zone) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Hours planned on a range of days, per day, week or month and optionally per status or priority.
+/// Swagger config:
+///   - Operation ID: get_workload
+///   - HTTP Method: GET
+///   - Path: /cards/workload
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Query Parameter: from (string in "YYYY-MM-DD" format)
+///   - Query Parameter: to (string in "YYYY-MM-DD" format, included)
+///   - Query Parameter: group_by (day, week or month, optionally followed by status or priority, such as "week,status")
+///   - Query Parameter: tz (IANA time zone the days are read in, optional, UTC by default)
+///   - Header: X-Time-Zone (same as tz, the query parameter wins)
+///   - Response: JSON Workload, the hours and cards of every period, zero when nothing is planned
+///   - Error 422: list of the fields that failed validation
+pub async fn get_workload(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+    _query: WorkloadQuery,
+    _time_zone: Tz,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _query.validate() {
+        let status = err.to_status_code();
+        return respond::<Workload>(Err(err), status);
+    }
+
+    match _db_manager.get_workload(_board_id, _query, _time_zone) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Restore a card from the trash, with the descendants trashed along with it.
+/// Swagger config:
+///   - Operation ID: restore_card
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/restore
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
+///   - Response: JSON representation of the restored card, with its ETag, under its closest live ancestor
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day of a restored card goes over a daily capacity under the reject policy
+pub async fn restore_card(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    match _db_manager.restore_card(_board_id, _id) {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch);
+            respond_checked(_branch, warnings, warp::http::StatusCode::OK)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<Card>(Err(err), status)
+        }
+    }
+}
+
 /// Delete a card by ID.
 /// Swagger config:
 ///   - Operation ID: delete_card
 ///   - HTTP Method: DELETE
 ///   - Path: /cards/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
 ///   - Path Parameter: id (integer)
-///   - Response: JSON representation of the deleted card
+///   - Header: If-Match (card ETag, optional)
+///   - Query Parameter: children ("cascade" or "reparent", required when the card has children)
+///   - Response: number of cards moved to the trash, their running timers stopped
+///   - Error 409: the card has children and no children policy was given
+///   - Error 412: the card changed since the ETag was read
 pub async fn delete_card(
+    _board_id: i64,
     _id: i64,
     _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+    _query: CardDeleteQuery,
 ) -> std::result::Result<impl warp::Reply, warp::Rejection> {
-    match _db_manager.delete_card(_id) {
+    match _db_manager.delete_card(_board_id, _id, _if_match, _query.children) {
         Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
-        Err(_) => respond(
-            Err(AppError::new("Internal server error", ErrorType::Internal)),
-            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-        ),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings_are_ascii_header_values() {
+        let encoded = percent_encode("user 3 (José) plans 9 hour(s), 100% of 9");
+
+        assert_eq!(encoded, "user 3 (Jos%C3%A9) plans 9 hour(s), 100%25 of 9");
+        assert!(warp::http::HeaderValue::from_str(&encoded).is_ok());
+        assert_eq!(percent_encode("tab\there"), "tab%09here");
     }
 }
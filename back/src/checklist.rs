@@ -0,0 +1,114 @@
+use crate::{
+    card::respond,
+    database::DBAccessManager,
+    domain::{ChecklistItem, ChecklistItemData},
+    validation::Validate,
+};
+
+/// Get the checklist of a card.
+/// Swagger config:
+///   - Operation ID: get_checklist
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/checklist
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON list of the checklist items, in order
+pub async fn get_checklist(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_checklist(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Add an item to the checklist of a card.
+/// Swagger config:
+///   - Operation ID: create_checklist_item
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/checklist
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: ChecklistItemData (added at the end without position)
+///   - Response: JSON representation of the created item
+///   - Error 422: list of the fields that failed validation
+pub async fn create_checklist_item(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: ChecklistItemData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<ChecklistItem>(Err(err), status);
+    }
+
+    match _db_manager.create_checklist_item(_board_id, _id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit an item of the checklist of a card.
+/// Swagger config:
+///   - Operation ID: edit_checklist_item
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/checklist/{item_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: item_id (integer)
+///   - Request Body: ChecklistItemData (moved when position is given)
+///   - Response: JSON representation of the edited item
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_checklist_item(
+    _board_id: i64,
+    _id: i64,
+    _item_id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: ChecklistItemData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<ChecklistItem>(Err(err), status);
+    }
+
+    match _db_manager.edit_checklist_item(_board_id, _id, _item_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete an item of the checklist of a card.
+/// Swagger config:
+///   - Operation ID: delete_checklist_item
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/checklist/{item_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: item_id (integer)
+///   - Response: number of deleted items
+pub async fn delete_checklist_item(
+    _board_id: i64,
+    _id: i64,
+    _item_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_checklist_item(_board_id, _id, _item_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
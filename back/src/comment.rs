@@ -0,0 +1,124 @@
+use crate::{
+    card::respond,
+    database::DBAccessManager,
+    domain::{Comment, CommentData, CommentPage, PageQuery},
+    validation::Validate,
+};
+
+/// Get the comments of a card.
+/// Swagger config:
+///   - Operation ID: get_comments
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/comments
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Query Parameter: page (integer, optional, 1 by default)
+///   - Query Parameter: per_page (integer, optional, 20 by default)
+///   - Response: JSON page of the comments, oldest first, with the total count
+///   - Error 422: list of the invalid pagination parameters
+pub async fn get_comments(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _query: PageQuery,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _query.validate() {
+        let status = err.to_status_code();
+        return respond::<CommentPage>(Err(err), status);
+    }
+
+    match _db_manager.get_comments(_board_id, _id, _query) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Comment a card.
+/// Swagger config:
+///   - Operation ID: create_comment
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/comments
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Header: X-Author (optional, recorded as the comment author)
+///   - Request Body: CommentData
+///   - Response: JSON representation of the created comment
+///   - Error 422: list of the fields that failed validation
+pub async fn create_comment(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: CommentData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Comment>(Err(err), status);
+    }
+
+    match _db_manager.create_comment(_board_id, _id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit a comment of a card.
+/// Swagger config:
+///   - Operation ID: edit_comment
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/comments/{comment_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: comment_id (integer)
+///   - Request Body: CommentData
+///   - Response: JSON representation of the edited comment
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_comment(
+    _board_id: i64,
+    _id: i64,
+    _comment_id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: CommentData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Comment>(Err(err), status);
+    }
+
+    match _db_manager.edit_comment(_board_id, _id, _comment_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete a comment of a card.
+/// Swagger config:
+///   - Operation ID: delete_comment
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/comments/{comment_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: comment_id (integer)
+///   - Response: number of deleted comments
+pub async fn delete_comment(
+    _board_id: i64,
+    _id: i64,
+    _comment_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_comment(_board_id, _id, _comment_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
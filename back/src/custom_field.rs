@@ -0,0 +1,108 @@
+use crate::{
+    card::respond,
+    database::DBAccessManager,
+    domain::{CustomField, CustomFieldData},
+    validation::Validate,
+};
+
+/// Add a custom field to the cards of a board.
+/// Swagger config:
+///   - Operation ID: create_custom_field
+///   - HTTP Method: POST
+///   - Path: /custom_field
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Request Body: CustomFieldData
+///   - Response: JSON representation of the created custom field
+///   - Error 409: the board already has a custom field with this name
+///   - Error 422: list of the fields that failed validation
+pub async fn create_custom_field(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: CustomFieldData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<CustomField>(Err(err), status);
+    }
+
+    match _db_manager.create_custom_field(_board_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get all custom fields of a board.
+/// Swagger config:
+///   - Operation ID: get_custom_fields
+///   - HTTP Method: GET
+///   - Path: /custom_field
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Response: JSON representation of the custom fields, sorted by name
+pub async fn get_custom_fields(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_custom_fields(_board_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit a custom field, renaming it renames the values stored on the cards.
+/// Swagger config:
+///   - Operation ID: edit_custom_field
+///   - HTTP Method: PUT
+///   - Path: /custom_field/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: CustomFieldData
+///   - Response: JSON representation of the edited custom field
+///   - Error 409: name already taken, or card values the new type or options reject
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_custom_field(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: CustomFieldData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<CustomField>(Err(err), status);
+    }
+
+    match _db_manager.edit_custom_field(_board_id, _id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete a custom field, removing its values from every card.
+/// Swagger config:
+///   - Operation ID: delete_custom_field
+///   - HTTP Method: DELETE
+///   - Path: /custom_field/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted custom fields
+pub async fn delete_custom_field(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_custom_field(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
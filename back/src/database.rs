@@ -1,13 +1,42 @@
 use super::errors::{AppError, ErrorType};
-use crate::domain::{Card, CardData};
-use chrono::NaiveDate;
+use crate::domain::{
+    day_bounds, local_date, Card, CardData, CardListQuery, CardPatch, CardSortKey, CardStatus,
+    CardView, ChildrenPolicy, DailyCapacity, Label, LabelMatch, PurgedCards, RevisionAction,
+    SortOrder, User,
+};
+use crate::schema::data;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use custom_field::JsonbContains;
 use diesel::{
-    pg::PgConnection,
+    expression::{BoxableExpression, IntoSql},
+    pg::{Pg, PgConnection},
     prelude::*,
     r2d2::{ConnectionManager, Pool, PooledConnection},
+    sql_types::Bool,
 };
+use serde_json::Value;
+use std::collections::HashMap;
 use warp::{reject, Filter};
 
+mod attachment;
+mod board;
+mod capacity;
+mod checklist;
+mod comment;
+mod custom_field;
+mod dependency;
+mod hierarchy;
+mod label;
+mod rank;
+mod recurrence;
+mod revision;
+mod template;
+mod time_entry;
+mod user;
+mod workflow;
+mod workload;
+
 /// Database connection pool creation
 pub type PgPool = Pool<ConnectionManager<PgConnection>>;
 
@@ -24,6 +53,7 @@ pub fn get_pg_pool(db_url: &str) -> PgPool {
 }
 
 /// Database access for warp routes
+/// The optional `X-Author` header is recorded in the history of the cards written
 /// # Arguments
 /// * `pool` - The database connection pool, type PgPool
 /// # Returns
@@ -34,101 +64,699 @@ pub fn with_db_access_manager(
 ) -> impl Filter<Extract = (DBAccessManager,), Error = warp::Rejection> + Clone {
     warp::any()
         .map(move || pool.clone())
-        .and_then(|pool: PgPool| async move {
-            match pool.get() {
-                Ok(conn) => Ok(DBAccessManager::new(conn)),
-                Err(err) => Err(reject::custom(AppError::new(
-                    format!("Error getting connection from pool: {}", err.to_string()).as_str(),
-                    ErrorType::Internal,
-                ))),
+        .and(warp::header::optional::<String>("x-author"))
+        .and_then(|pool: PgPool, author: Option<String>| async move {
+            match DBAccessManager::from_pool(&pool) {
+                Ok(db_manager) => Ok(db_manager.with_author(author)),
+                Err(err) => Err(reject::custom(err)),
             }
         })
 }
 
+/// Database access for warp routes putting cards into a workflow column
+/// `X-Wip-Override` holding the `ADMIN_TOKEN` of the server lets an admin go over the WIP limits,
+/// any other value is refused, every value when the server has no admin token
+/// # Arguments
+/// * `pool` - The database connection pool, type PgPool
+/// * `admin_token` - The admin token of the server, `None` when overrides are disabled
+/// # Returns
+/// * `Filter` - The database access filter, type Filter
+///
+pub fn with_wip_access_manager(
+    pool: PgPool,
+    admin_token: Option<String>,
+) -> impl Filter<Extract = (DBAccessManager,), Error = warp::Rejection> + Clone {
+    with_db_access_manager(pool)
+        .and(warp::header::optional::<String>("x-wip-override"))
+        .and_then(move |db_manager: DBAccessManager, token: Option<String>| {
+            let allowed = match (token, admin_token.as_deref()) {
+                (None, _) => Ok(false),
+                (Some(token), Some(admin_token)) if token == admin_token => Ok(true),
+                (Some(_), _) => Err(reject::custom(AppError::new(
+                    "X-Wip-Override does not hold the admin token",
+                    ErrorType::Unauthorized,
+                ))),
+            };
+            async move { allowed.map(|allowed| db_manager.with_wip_override(allowed)) }
+        })
+}
+
+/// Database pool for warp routes taking their connections themselves,
+/// such as uploads that must not hold one while the request body streams
+/// # Arguments
+/// * `pool` - The database connection pool, type PgPool
+/// # Returns
+/// * `Filter` - The pool filter, type Filter
+///
+pub fn with_pool(
+    pool: PgPool,
+) -> impl Filter<Extract = (PgPool,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || pool.clone())
+}
+
+/// Unique violations on `data` can only come from two occurrences of a recurrence on one date
+const OCCURRENCE_DATE_TAKEN: &str =
+    "another occurrence of this recurrence is already planned on this date, trashed ones included";
+
+/// Cards planned on a day read in a time zone: by their date without a due time,
+/// by their due time falling in the day otherwise
+fn planned_on<QS>(
+    day: NaiveDate,
+    time_zone: Tz,
+) -> Box<dyn BoxableExpression<QS, Pg, SqlType = Bool>>
+where
+    data::date: SelectableExpression<QS>,
+    data::due_at: SelectableExpression<QS>,
+{
+    let (start, end) = day_bounds(day, time_zone);
+
+    Box::new(
+        data::due_at
+            .is_null()
+            .and(data::date.eq(day))
+            .or(data::due_at.ge(start).and(data::due_at.lt(end))),
+    )
+}
+
 /// Type alias for a database connection pool
 type PooledPg = PooledConnection<ConnectionManager<PgConnection>>;
 
 /// Database connection pool object
 pub struct DBAccessManager {
     pub connection: PooledPg,
+    /// Who performs the writes, as sent by the client
+    pub author: Option<String>,
+    /// Cards may go over the WIP limits of the columns
+    pub wip_override: bool,
+    /// Server default of the daily capacities, `None` skips the capacity checks
+    pub capacity: Option<DailyCapacity>,
+    /// Cards may go over the daily capacities under the reject policy
+    pub allow_overbook: bool,
 }
 
 impl DBAccessManager {
     /// New Database connection pool object
     pub fn new(connection: PooledPg) -> DBAccessManager {
-        DBAccessManager { connection }
+        DBAccessManager {
+            connection,
+            author: None,
+            wip_override: false,
+            capacity: None,
+            allow_overbook: false,
+        }
+    }
+
+    /// New Database connection pool object, on a connection taken from the pool
+    pub fn from_pool(pool: &PgPool) -> Result<DBAccessManager, AppError> {
+        pool.get().map(DBAccessManager::new).map_err(|err| {
+            AppError::new(
+                format!("Error getting connection from pool: {}", err).as_str(),
+                ErrorType::Internal,
+            )
+        })
+    }
+
+    /// Attribute the writes of this object to an author
+    pub fn with_author(mut self, author: Option<String>) -> DBAccessManager {
+        self.author = author.filter(|author| !author.trim().is_empty());
+        self
+    }
+
+    /// Let the writes of this object go over the WIP limits
+    pub fn with_wip_override(mut self, wip_override: bool) -> DBAccessManager {
+        self.wip_override = wip_override;
+        self
     }
 
-    pub fn create_card(&self, dto: CardData) -> Result<Card, AppError> {
+    /// Check the days of the cards this object writes against the daily capacities
+    pub fn with_capacity(
+        mut self,
+        capacity: DailyCapacity,
+        allow_overbook: bool,
+    ) -> DBAccessManager {
+        self.capacity = Some(capacity);
+        self.allow_overbook = allow_overbook;
+        self
+    }
+
+    pub fn create_card(&self, _board_id: i64, mut dto: CardData) -> Result<Card, AppError> {
         use super::schema::data;
 
-        diesel::insert_into(data::table)
-            .values(&dto)
-            .get_result(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while creating card"))
+        dto.custom = Some(self.check_custom_values(
+            _board_id,
+            dto.custom.unwrap_or_else(|| serde_json::json!({})),
+        )?);
+        let dto = dto.align_date();
+        self.connection.transaction(|| {
+            self.check_status_change(_board_id, None, dto.status)?;
+            self.check_wip_limit(_board_id, None, dto.status)?;
+            let rank = self.end_of_column_rank(_board_id, dto.status)?;
+
+            let card: Card = diesel::insert_into(data::table)
+                .values((&dto, data::board_id.eq(_board_id), data::rank.eq(rank)))
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while creating card"))?;
+
+            self.check_capacity(&card)?;
+            self.record_revision(&card, RevisionAction::Create)?;
+            Ok(card)
+        })
     }
 
-    pub fn get_card_by_id(&self, _id: i64) -> Result<Card, AppError> {
+    pub fn get_card_by_id(&self, _board_id: i64, _id: i64) -> Result<Card, AppError> {
         use crate::schema::data::dsl::*;
 
         data.filter(id.eq(_id))
+            .filter(board_id.eq(_board_id))
+            .filter(deleted_at.is_null())
             .first(&self.connection)
             .map_err(|err| AppError::from_diesel_err(err, "while retrieving contract"))
     }
 
-    pub fn check_total_duration_on_date(&self, date: NaiveDate) -> Result<i64, AppError> {
-        use crate::schema::data::dsl::*;
+    /// Attach to each card the rows the read routes embed in it
+    pub fn card_views(&self, cards: Vec<Card>) -> Result<Vec<CardView>, AppError> {
+        use crate::schema::{card_assignees, card_labels, labels, users};
+
+        let ids: Vec<i64> = cards.iter().map(|card| card.id).collect();
 
-        let total_duration = data
-            .filter(date.eq(date))
-            .select(diesel::dsl::sum(duration))
-            .first::<Option<i64>>(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while retrieving total duration"))?
-            .unwrap_or(0);
+        let rows: Vec<(i64, Label)> = card_labels::table
+            .inner_join(labels::table)
+            .filter(card_labels::card_id.eq_any(&ids))
+            .order((labels::name.asc(), labels::id.asc()))
+            .select((card_labels::card_id, labels::all_columns))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving card labels"))?;
+
+        let mut labels_by_card: HashMap<i64, Vec<Label>> = HashMap::new();
+        for (card_id, label) in rows {
+            labels_by_card.entry(card_id).or_default().push(label);
+        }
 
-        Ok(total_duration)
+        let rows: Vec<(i64, User)> = card_assignees::table
+            .inner_join(users::table)
+            .filter(card_assignees::card_id.eq_any(&ids))
+            .order((users::name.asc(), users::id.asc()))
+            .select((card_assignees::card_id, users::all_columns))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving card assignees"))?;
+
+        let mut assignees_by_card: HashMap<i64, Vec<User>> = HashMap::new();
+        for (card_id, user) in rows {
+            assignees_by_card.entry(card_id).or_default().push(user);
+        }
+        let mut blockers = self.card_blockers(&ids)?;
+        let checklists = self.checklist_progress(&ids)?;
+        let comment_counts = self.comment_counts(&ids)?;
+        let tracked = self.tracked_minutes(&ids)?;
+        let rollups = self.child_rollups(&ids)?;
+
+        Ok(cards
+            .into_iter()
+            .map(|card| {
+                let blocked_by = blockers.remove(&card.id).unwrap_or_default();
+
+                CardView {
+                    labels: labels_by_card.remove(&card.id).unwrap_or_default(),
+                    assignees: assignees_by_card.remove(&card.id).unwrap_or_default(),
+                    blocked: blocked_by
+                        .iter()
+                        .any(|(_, status)| *status != CardStatus::Completed),
+                    blocked_by: blocked_by.into_iter().map(|(id, _)| id).collect(),
+                    tracked_minutes: tracked.get(&card.id).copied().unwrap_or_default(),
+                    rollup: rollups.get(&card.id).copied(),
+                    checklist: checklists.get(&card.id).copied().unwrap_or_default(),
+                    comment_count: comment_counts.get(&card.id).copied().unwrap_or_default(),
+                    card,
+                }
+            })
+            .collect())
     }
 
-    pub fn get_all_cards(&self) -> Result<Vec<Card>, AppError> {
+    pub fn card_view(&self, card: Card) -> Result<CardView, AppError> {
+        self.card_views(vec![card]).map(|mut views| views.remove(0))
+    }
+
+    pub fn get_all_cards(
+        &self,
+        _board_id: i64,
+        query: CardListQuery,
+    ) -> Result<Vec<CardView>, AppError> {
+        use crate::schema::{card_assignees, card_labels, data::dsl::*};
+
+        self.get_board(_board_id)?;
+
+        let mut statement = data
+            .filter(board_id.eq(_board_id))
+            .filter(deleted_at.is_null())
+            .into_boxed();
+
+        if let Some(since) = query.updated_since {
+            statement = statement.filter(updated_at.ge(since));
+        }
+
+        if !query.label.is_empty() {
+            let with_label = |labels: &[i64]| {
+                card_labels::table
+                    .filter(card_labels::label_id.eq_any(labels.to_vec()))
+                    .select(card_labels::card_id)
+            };
+
+            statement = match query.label_match.unwrap_or(LabelMatch::Any) {
+                LabelMatch::Any => statement.filter(id.eq_any(with_label(&query.label))),
+                LabelMatch::All => query.label.iter().fold(statement, |statement, label| {
+                    statement.filter(id.eq_any(with_label(std::slice::from_ref(label))))
+                }),
+            };
+        }
+
+        if !query.custom.is_empty() {
+            let values = self.custom_filter(_board_id, &query.custom)?;
+            statement = statement.filter(JsonbContains::new(
+                custom,
+                values.into_sql::<diesel::sql_types::Jsonb>(),
+            ));
+        }
+
+        if !query.assignee.is_empty() {
+            statement = statement.filter(
+                id.eq_any(
+                    card_assignees::table
+                        .filter(card_assignees::user_id.eq_any(query.assignee))
+                        .select(card_assignees::card_id),
+                ),
+            );
+        }
+
+        let descending = query.order == Some(SortOrder::Desc);
+        statement = match (query.sort.unwrap_or(CardSortKey::Rank), descending) {
+            (CardSortKey::Rank, false) => statement.order((status.asc(), rank.asc())),
+            (CardSortKey::Rank, true) => statement.order((status.desc(), rank.desc())),
+            (CardSortKey::Id, false) => statement.order(id.asc()),
+            (CardSortKey::Id, true) => statement.order(id.desc()),
+            (CardSortKey::CreatedAt, false) => statement.order(created_at.asc()),
+            (CardSortKey::CreatedAt, true) => statement.order(created_at.desc()),
+            (CardSortKey::UpdatedAt, false) => statement.order(updated_at.asc()),
+            (CardSortKey::UpdatedAt, true) => statement.order(updated_at.desc()),
+        };
+
+        let cards = statement
+            .then_order_by(id.asc())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving all cards"))?;
+
+        self.card_views(cards)
+    }
+
+    /// Lock a card for the rest of the transaction and check its version
+    /// # Arguments
+    /// * `_board_id` - Board the card must belong to
+    /// * `_id` - Id of the card
+    /// * `expected_version` - Version sent in `If-Match`, `None` skips the check
+    /// # Returns
+    /// * `AppError` - PreconditionFailed when the stored version differs
+    ///
+    pub fn lock_card(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        expected_version: Option<i32>,
+    ) -> Result<Card, AppError> {
         use crate::schema::data::dsl::*;
 
-        data.load(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while retrieving all cards"))
+        let card: Card = data
+            .filter(id.eq(_id))
+            .filter(board_id.eq(_board_id))
+            .filter(deleted_at.is_null())
+            .for_update()
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while locking card"))?;
+
+        match expected_version {
+            Some(expected) if expected != card.version => Err(AppError::new(
+                format!(
+                    "card has been modified, current version is {} but {} was expected",
+                    card.version, expected
+                )
+                .as_str(),
+                ErrorType::PreconditionFailed,
+            )),
+            _ => Ok(card),
+        }
+    }
+
+    /// New rank of a card changing column, it goes to the end of the new one
+    /// # Returns
+    /// * `None` - The card stays in its column and keeps its rank
+    ///
+    pub fn status_change_rank(
+        &self,
+        _board_id: i64,
+        card: &Card,
+        new_status: CardStatus,
+    ) -> Result<Option<String>, AppError> {
+        if card.status == new_status {
+            return Ok(None);
+        }
+
+        self.end_of_column_rank(_board_id, new_status).map(Some)
     }
 
-    pub fn edit_card(&self, _id: i64, dto: CardData) -> Result<Card, AppError> {
+    pub fn edit_card(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        mut dto: CardData,
+        expected_version: Option<i32>,
+    ) -> Result<Card, AppError> {
         use crate::schema::data::dsl::*;
 
-        let updated = diesel::update(data.filter(id.eq(_id)))
-            .set((
-                title.eq(dto.title),
-                description.eq(dto.description),
-                date.eq(dto.date),
-                priority.eq(dto.priority),
-                duration.eq(dto.duration),
-                status.eq(dto.status),
-            ))
-            .execute(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while updating card"))?;
-
-        if updated == 0 {
-            return Err(AppError::new("card not found", ErrorType::NotFound));
+        // Absent values keep the stored ones, as for the clients predating custom fields
+        if let Some(values) = dto.custom.take() {
+            dto.custom = Some(self.check_custom_values(_board_id, values)?);
         }
+        let dto = dto.align_date();
+        self.connection.transaction(|| {
+            let previous = self.lock_card(_board_id, _id, expected_version)?;
+            self.check_status_change(_board_id, Some(previous.status), dto.status)?;
+            self.check_blockers(&previous, dto.status)?;
+            self.check_wip_limit(_board_id, Some(&previous), dto.status)?;
+            let new_rank = self.status_change_rank(_board_id, &previous, dto.status)?;
 
-        self.get_card_by_id(_id)
+            let card: Card = diesel::update(data.filter(id.eq(_id)))
+                .set((
+                    new_rank.map(|new_rank| rank.eq(new_rank)),
+                    title.eq(dto.title),
+                    description.eq(dto.description),
+                    date.eq(dto.date),
+                    priority.eq(dto.priority),
+                    duration.eq(dto.duration),
+                    status.eq(dto.status),
+                    due_at.eq(dto.due_at),
+                    time_zone.eq(dto.time_zone),
+                    dto.custom.map(|values| custom.eq(values)),
+                ))
+                .get_result(&self.connection)
+                .map_err(|err| {
+                    AppError::from_unique_violation(
+                        err,
+                        "while updating card",
+                        OCCURRENCE_DATE_TAKEN,
+                    )
+                })?;
+
+            if card.date != previous.date || card.duration != previous.duration {
+                self.check_capacity(&card)?;
+            }
+            if card.version != previous.version {
+                self.record_revision(&card, RevisionAction::Update)?;
+            }
+            Ok(card)
+        })
     }
 
-    pub fn delete_card(&self, _id: i64) -> Result<usize, AppError> {
+    pub fn patch_card(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        mut patch: CardPatch,
+        expected_version: Option<i32>,
+    ) -> Result<Card, AppError> {
         use crate::schema::data::dsl::*;
 
-        let deleted = diesel::delete(data.filter(id.eq(_id)))
-            .execute(&self.connection)
-            .map_err(|err| AppError::from_diesel_err(err, "while deleting card"))?;
+        self.connection.transaction(|| {
+            let card = self.lock_card(_board_id, _id, expected_version)?;
 
-        if deleted == 0 {
-            return Err(AppError::new("card not found", ErrorType::NotFound));
-        }
+            // A due time decides the day of the card, whichever of them is patched
+            let new_due_at = patch.due_at.unwrap_or(card.due_at);
+            let new_time_zone = patch.time_zone.clone().unwrap_or(card.time_zone.clone());
+            if let Some(new_due_at) = new_due_at {
+                let day = local_date(new_due_at, new_time_zone.as_deref());
+                if patch.date.is_some() || day != card.date {
+                    patch.date = Some(day);
+                }
+            }
+
+            // Custom values are merged, a null value removes the field
+            if let Some(values) = patch.custom.take() {
+                let mut merged = card.custom.clone();
+                match (&mut merged, values) {
+                    (Value::Object(merged), Value::Object(values)) => {
+                        for (name, value) in values {
+                            merged.insert(name, value);
+                        }
+                    }
+                    (_, values) => merged = values,
+                }
+                patch.custom = Some(self.check_custom_values(_board_id, merged)?);
+            }
+
+            let mut new_rank = None;
+            if let Some(new_status) = patch.status {
+                self.check_status_change(_board_id, Some(card.status), new_status)?;
+                self.check_blockers(&card, new_status)?;
+                self.check_wip_limit(_board_id, Some(&card), new_status)?;
+                new_rank = self.status_change_rank(_board_id, &card, new_status)?;
+            }
+
+            // Diesel refuses an empty changeset, there is nothing to write anyway
+            if patch.is_empty() {
+                return Ok(card);
+            }
+
+            let patched: Card = diesel::update(data.filter(id.eq(_id)))
+                .set((&patch, new_rank.map(|new_rank| rank.eq(new_rank))))
+                .get_result(&self.connection)
+                .map_err(|err| {
+                    AppError::from_unique_violation(
+                        err,
+                        "while patching card",
+                        OCCURRENCE_DATE_TAKEN,
+                    )
+                })?;
+
+            if patched.date != card.date || patched.duration != card.duration {
+                self.check_capacity(&patched)?;
+            }
+            if patched.version != card.version {
+                self.record_revision(&patched, RevisionAction::Update)?;
+            }
+            Ok(patched)
+        })
+    }
+
+    /// Move a card to the trash, it stays restorable until purged
+    /// # Arguments
+    /// * `_board_id` - Board the card must belong to
+    /// * `_id` - Id of the card
+    /// * `expected_version` - Version sent in `If-Match`, `None` skips the check
+    /// * `children` - What happens to its live children, required when it has some
+    /// # Returns
+    /// * `usize` - Number of cards moved to the trash
+    ///
+    pub fn delete_card(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        expected_version: Option<i32>,
+        children: Option<ChildrenPolicy>,
+    ) -> Result<usize, AppError> {
+        use crate::schema::data::dsl::*;
+
+        self.connection.transaction(|| {
+            self.lock_hierarchy()?;
+            let card = self.lock_card(_board_id, _id, expected_version)?;
+
+            let mut trashed = vec![card.id];
+            let child_count = self.children_of(&[card.id])?.len();
+            match children {
+                _ if child_count == 0 => {}
+                None => {
+                    return Err(AppError::new(
+                        format!(
+                            "card has {} children, delete it with children=cascade or children=reparent",
+                            child_count
+                        )
+                        .as_str(),
+                        ErrorType::Conflict,
+                    ))
+                }
+                Some(ChildrenPolicy::Cascade) => {
+                    trashed.extend(self.descendants_of(card.id)?.iter().map(|child| child.id));
+                }
+                Some(ChildrenPolicy::Reparent) => {
+                    let children: Vec<Card> = diesel::update(
+                        data.filter(parent_id.eq(card.id))
+                            .filter(deleted_at.is_null()),
+                    )
+                    .set(parent_id.eq(card.parent_id))
+                    .get_results(&self.connection)
+                    .map_err(|err| AppError::from_diesel_err(err, "while moving children"))?;
+
+                    for child in &children {
+                        self.record_revision(child, RevisionAction::Update)?;
+                    }
+                }
+            }
+
+            let cards: Vec<Card> = diesel::update(data.filter(id.eq_any(&trashed)))
+                .set(deleted_at.eq(diesel::dsl::now))
+                .get_results(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while deleting card"))?;
+            self.stop_timers(&trashed)?;
+
+            for card in &cards {
+                self.record_revision(card, RevisionAction::Delete)?;
+            }
+            Ok(cards.len())
+        })
+    }
+
+    pub fn get_trash(&self, _board_id: i64) -> Result<Vec<Card>, AppError> {
+        use crate::schema::data::dsl::*;
+
+        data.filter(board_id.eq(_board_id))
+            .filter(deleted_at.is_not_null())
+            .order((deleted_at.desc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving trash"))
+    }
 
-        Ok(deleted)
+    /// Bring a card back from the trash, with the descendants a cascade delete trashed along with it
+    /// A card whose parent is still in the trash goes under its closest live ancestor
+    pub fn restore_card(&self, _board_id: i64, _id: i64) -> Result<Card, AppError> {
+        use crate::schema::data::dsl::*;
+
+        self.connection.transaction(|| {
+            self.lock_hierarchy()?;
+            let trashed: Card = data
+                .filter(id.eq(_id))
+                .filter(board_id.eq(_board_id))
+                .filter(deleted_at.is_not_null())
+                .for_update()
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while restoring card"))?;
+
+            // A cascade delete trashes the whole subtree in one statement, at one instant
+            let mut restored = vec![trashed.clone()];
+            let mut frontier = vec![trashed.id];
+            while !frontier.is_empty() {
+                let children: Vec<Card> = data
+                    .filter(parent_id.eq_any(&frontier))
+                    .filter(deleted_at.eq(trashed.deleted_at))
+                    .for_update()
+                    .load(&self.connection)
+                    .map_err(|err| AppError::from_diesel_err(err, "while restoring children"))?;
+                frontier = children.iter().map(|child| child.id).collect();
+                restored.extend(children);
+            }
+
+            let mut parent = trashed.parent_id;
+            while let Some(ancestor) = parent {
+                let (ancestor_parent, ancestor_deleted_at): (Option<i64>, Option<DateTime<Utc>>) =
+                    data.filter(id.eq(ancestor))
+                        .select((parent_id, deleted_at))
+                        .first(&self.connection)
+                        .map_err(|err| AppError::from_diesel_err(err, "while retrieving parent"))?;
+                if ancestor_deleted_at.is_none() {
+                    break;
+                }
+                parent = ancestor_parent;
+            }
+
+            let mut restored_cards = Vec::with_capacity(restored.len());
+            for card in restored {
+                // The card comes back into its column
+                self.check_wip_limit(_board_id, Some(&card), card.status)?;
+
+                let new_parent = if card.id == trashed.id {
+                    parent
+                } else {
+                    card.parent_id
+                };
+                let card: Card = diesel::update(data.filter(id.eq(card.id)))
+                    .set((
+                        deleted_at.eq(None::<DateTime<Utc>>),
+                        parent_id.eq(new_parent),
+                    ))
+                    .get_result(&self.connection)
+                    .map_err(|err| AppError::from_diesel_err(err, "while restoring card"))?;
+
+                // Its hours are planned again
+                self.check_capacity(&card)?;
+                self.record_revision(&card, RevisionAction::Restore)?;
+                restored_cards.push(card);
+            }
+
+            Ok(restored_cards.swap_remove(0))
+        })
+    }
+
+    /// Permanently delete a card that is already in the trash
+    pub fn purge_card(&self, _board_id: i64, _id: i64) -> Result<PurgedCards, AppError> {
+        use crate::schema::{attachments, data::dsl::*};
+
+        self.connection.transaction(|| {
+            // Locked so a concurrent restore cannot bring it back between the reads and the delete
+            let trashed: Vec<i64> = data
+                .filter(id.eq(_id))
+                .filter(board_id.eq(_board_id))
+                .filter(deleted_at.is_not_null())
+                .select(id)
+                .for_update()
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while purging card"))?;
+
+            // Attachment rows go with the card, their files are removed by the caller
+            let attachment_ids: Vec<i64> = attachments::table
+                .filter(attachments::card_id.eq_any(&trashed))
+                .select(attachments::id)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while purging card"))?;
+
+            let deleted = diesel::delete(data.filter(id.eq_any(&trashed)))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while purging card"))?;
+
+            if deleted == 0 {
+                return Err(AppError::new(
+                    "card not found in trash",
+                    ErrorType::NotFound,
+                ));
+            }
+
+            Ok(PurgedCards {
+                cards: deleted,
+                attachment_ids,
+            })
+        })
+    }
+
+    /// Permanently delete every card trashed before `older_than`
+    pub fn purge_trash(&self, older_than: DateTime<Utc>) -> Result<PurgedCards, AppError> {
+        use crate::schema::{attachments, data::dsl::*};
+
+        self.connection.transaction(|| {
+            let expired: Vec<i64> = data
+                .filter(deleted_at.lt(older_than))
+                .select(id)
+                .for_update()
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while purging trash"))?;
+
+            let attachment_ids: Vec<i64> = attachments::table
+                .filter(attachments::card_id.eq_any(&expired))
+                .select(attachments::id)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while purging trash"))?;
+
+            let cards = diesel::delete(data.filter(id.eq_any(&expired)))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while purging trash"))?;
+
+            Ok(PurgedCards {
+                cards,
+                attachment_ids,
+            })
+        })
     }
 }
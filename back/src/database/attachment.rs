@@ -0,0 +1,92 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Attachment, NewAttachment},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+
+impl DBAccessManager {
+    pub fn get_attachments(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+    ) -> Result<Vec<Attachment>, AppError> {
+        use crate::schema::attachments::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        attachments
+            .filter(card_id.eq(_card_id))
+            .order(id.asc())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving attachments"))
+    }
+
+    /// Attachment of a live card of the board
+    pub fn get_attachment(&self, _board_id: i64, _id: i64) -> Result<Attachment, AppError> {
+        use crate::schema::{attachments, data};
+
+        attachments::table
+            .inner_join(data::table)
+            .filter(attachments::id.eq(_id))
+            .filter(data::board_id.eq(_board_id))
+            .filter(data::deleted_at.is_null())
+            .select(attachments::all_columns)
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving attachment"))
+    }
+
+    /// Record the attachments of a card, `store` moves each file in place once its id is known
+    /// Nothing is recorded when a file cannot be stored
+    /// # Arguments
+    /// * `_board_id` - Board the card must belong to
+    /// * `_card_id` - Id of the card
+    /// * `uploads` - Metadata of the uploaded files
+    /// * `store` - Moves the file of an upload to the path of its attachment
+    ///
+    pub fn create_attachments<F>(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        uploads: Vec<NewAttachment>,
+        store: F,
+    ) -> Result<Vec<Attachment>, AppError>
+    where
+        F: Fn(usize, &Attachment) -> std::io::Result<()>,
+    {
+        use crate::schema::attachments;
+
+        self.connection.transaction(|| {
+            self.lock_card(_board_id, _card_id, None)?;
+
+            let created: Vec<Attachment> = diesel::insert_into(attachments::table)
+                .values(&uploads)
+                .get_results(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while creating attachments"))?;
+
+            for (index, attachment) in created.iter().enumerate() {
+                store(index, attachment).map_err(|err| {
+                    AppError::new(
+                        format!("while storing attachment: {}", err).as_str(),
+                        ErrorType::Internal,
+                    )
+                })?;
+            }
+
+            Ok(created)
+        })
+    }
+
+    /// Delete an attachment, its file is left to the caller
+    pub fn delete_attachment(&self, _board_id: i64, _id: i64) -> Result<Attachment, AppError> {
+        use crate::schema::attachments;
+
+        let attachment = self.get_attachment(_board_id, _id)?;
+
+        diesel::delete(attachments::table.filter(attachments::id.eq(attachment.id)))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting attachment"))?;
+
+        Ok(attachment)
+    }
+}
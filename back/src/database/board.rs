@@ -0,0 +1,93 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Board, BoardData, WorkflowData, DEFAULT_BOARD_ID},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+
+/// Unique violations on `boards` can only come from the board name
+const BOARD_NAME_TAKEN: &str = "a board with this name already exists";
+
+impl DBAccessManager {
+    pub fn create_board(&self, dto: BoardData) -> Result<Board, AppError> {
+        use crate::schema::boards;
+
+        self.connection.transaction(|| {
+            let board: Board = diesel::insert_into(boards::table)
+                .values(&dto)
+                .get_result(&self.connection)
+                .map_err(|err| {
+                    AppError::from_unique_violation(err, "while creating board", BOARD_NAME_TAKEN)
+                })?;
+
+            self.insert_workflow_columns(board.id, &WorkflowData::default_columns())?;
+            Ok(board)
+        })
+    }
+
+    pub fn get_board(&self, _id: i64) -> Result<Board, AppError> {
+        use crate::schema::boards::dsl::*;
+
+        boards
+            .filter(id.eq(_id))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving board"))
+    }
+
+    pub fn get_all_boards(&self) -> Result<Vec<Board>, AppError> {
+        use crate::schema::boards::dsl::*;
+
+        boards
+            .order(id.asc())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving all boards"))
+    }
+
+    pub fn edit_board(&self, _id: i64, dto: BoardData) -> Result<Board, AppError> {
+        use crate::schema::boards::dsl::*;
+
+        diesel::update(boards.filter(id.eq(_id)))
+            .set(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while updating board", BOARD_NAME_TAKEN)
+            })
+    }
+
+    /// Delete an empty board, cards (trashed ones included) must be moved or purged first
+    pub fn delete_board(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::{boards, data};
+
+        if _id == DEFAULT_BOARD_ID {
+            return Err(AppError::new(
+                "the default board cannot be deleted",
+                ErrorType::BadRequest,
+            ));
+        }
+
+        self.connection.transaction(|| {
+            let board: Board = boards::table
+                .filter(boards::id.eq(_id))
+                .for_update()
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving board"))?;
+
+            let cards: i64 = data::table
+                .filter(data::board_id.eq(board.id))
+                .count()
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while counting board cards"))?;
+
+            if cards > 0 {
+                return Err(AppError::new(
+                    format!("board still holds {} card(s)", cards).as_str(),
+                    ErrorType::Conflict,
+                ));
+            }
+
+            diesel::delete(boards::table.filter(boards::id.eq(board.id)))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while deleting board"))
+        })
+    }
+}
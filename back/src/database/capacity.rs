@@ -0,0 +1,207 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{zone_or_utc, CapacityPolicy, Card, User},
+    errors::{AppError, ErrorType, FieldError},
+};
+use diesel::{prelude::*, sql_types::Text};
+use std::{collections::HashMap, iter};
+
+/// Capacity of a day going over, with the hours planned on it
+struct Overrun {
+    /// Who the capacity belongs to, such as `the board` or `the unassigned cards`
+    owner: String,
+    capacity: i64,
+    planned: i64,
+    /// Hours of the day left before the written card
+    remaining: i64,
+}
+
+impl Overrun {
+    fn message(&self, card: &Card) -> String {
+        format!(
+            "{} hour(s) planned on {} for {}, over a daily capacity of {}, {} hour(s) remaining without card {}",
+            self.planned, card.date, self.owner, self.capacity, self.remaining, card.id
+        )
+    }
+
+    /// The numbers of the message, for clients that act on them
+    fn fields(&self) -> Vec<FieldError> {
+        vec![
+            FieldError::new("owner", &self.owner),
+            FieldError::new("capacity", &self.capacity.to_string()),
+            FieldError::new("total", &self.planned.to_string()),
+            FieldError::new("remaining", &self.remaining.to_string()),
+        ]
+    }
+}
+
+impl DBAccessManager {
+    /// Capacities the day of a card goes over, the card included
+    /// A board with a capacity counts its live cards, a board without one its live unassigned cards
+    /// when the card is unassigned, and each assignee their live cards of every board,
+    /// cards with a due time counting on the day it falls on in the zone of the card
+    fn overruns(&self, card: &Card) -> Result<Vec<Overrun>, AppError> {
+        use crate::schema::{card_assignees, data, users};
+
+        let default = match self.capacity {
+            Some(capacity) => capacity,
+            None => return Ok(Vec::new()),
+        };
+        let overrun = |owner: String, capacity: i32, planned: i64| {
+            let capacity = i64::from(capacity);
+            (planned > capacity).then(|| Overrun {
+                owner,
+                capacity,
+                planned,
+                remaining: (capacity - planned + i64::from(card.duration)).max(0),
+            })
+        };
+
+        // The day of the card in its zone, as the check route reads it there
+        let zone = zone_or_utc(card.time_zone.as_deref());
+
+        let board_capacity = self.get_board(card.board_id)?.daily_capacity;
+        let assignees: Vec<User> = card_assignees::table
+            .inner_join(users::table)
+            .filter(card_assignees::card_id.eq(card.id))
+            .order((users::name.asc(), users::id.asc()))
+            .select(users::all_columns)
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving card assignees"))?;
+
+        // A board total is held against a capacity the board sets. Without one, the unassigned cards
+        // the check route reports are one person's day, held against the default
+        let mut overruns: Vec<Overrun> = Vec::new();
+        if let Some(capacity) = board_capacity {
+            let planned: Option<i64> = data::table
+                .filter(data::board_id.eq(card.board_id))
+                .filter(super::planned_on(card.date, zone))
+                .filter(data::deleted_at.is_null())
+                .select(diesel::dsl::sum(data::duration))
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving total duration"))?;
+
+            overruns.extend(overrun(
+                "the board".to_string(),
+                capacity,
+                planned.unwrap_or_default(),
+            ));
+        } else if assignees.is_empty() {
+            let assigned = card_assignees::table
+                .filter(card_assignees::card_id.eq(data::id))
+                .select(card_assignees::card_id);
+            let planned: Option<i64> = data::table
+                .filter(data::board_id.eq(card.board_id))
+                .filter(super::planned_on(card.date, zone))
+                .filter(data::deleted_at.is_null())
+                .filter(diesel::dsl::not(diesel::dsl::exists(assigned)))
+                .select(diesel::dsl::sum(data::duration))
+                .first(&self.connection)
+                .map_err(|err| {
+                    AppError::from_diesel_err(err, "while retrieving unassigned duration")
+                })?;
+
+            overruns.extend(overrun(
+                "the unassigned cards".to_string(),
+                default.hours,
+                planned.unwrap_or_default(),
+            ));
+        }
+        if assignees.is_empty() {
+            return Ok(overruns);
+        }
+
+        let ids: Vec<i64> = assignees.iter().map(|user| user.id).collect();
+        let rows: Vec<(i64, i32)> = card_assignees::table
+            .inner_join(data::table)
+            .filter(card_assignees::user_id.eq_any(&ids))
+            .filter(super::planned_on(card.date, zone))
+            .filter(data::deleted_at.is_null())
+            .select((card_assignees::user_id, data::duration))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving assigned durations"))?;
+
+        let mut planned: HashMap<i64, i64> = HashMap::new();
+        for (user_id, duration) in rows {
+            *planned.entry(user_id).or_default() += i64::from(duration);
+        }
+        overruns.extend(assignees.into_iter().filter_map(|user| {
+            overrun(
+                format!("user {} ({})", user.id, user.name),
+                user.daily_capacity.unwrap_or(default.hours),
+                planned.get(&user.id).copied().unwrap_or_default(),
+            )
+        }));
+
+        Ok(overruns)
+    }
+
+    /// Serialize the capacity checks of the board of a card and of each of its assignees until the end
+    /// of the transaction: two cards written at once could both fit in the hours left
+    /// Not keyed by date, cards read in different zones count on days that overlap
+    fn lock_capacities(&self, card: &Card) -> Result<(), AppError> {
+        use crate::schema::card_assignees;
+
+        let user_ids: Vec<i64> = card_assignees::table
+            .filter(card_assignees::card_id.eq(card.id))
+            .select(card_assignees::user_id)
+            .order(card_assignees::user_id.asc())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving card assignees"))?;
+
+        // Always taken in the same order, the board then the users by id, so checks cannot deadlock
+        let keys = iter::once(format!("capacity board {}", card.board_id)).chain(
+            user_ids
+                .iter()
+                .map(|user_id| format!("capacity user {}", user_id)),
+        );
+        for key in keys {
+            diesel::sql_query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                .bind::<Text, _>(key)
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while locking daily capacity"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Capacities the day of a written card goes over, as the `X-Capacity-Warning` messages
+    pub fn capacity_warnings(&self, card: &Card) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .overruns(card)?
+            .iter()
+            .map(|overrun| overrun.message(card))
+            .collect())
+    }
+
+    /// Check the day of a card just written fits the daily capacities, within its transaction
+    /// The capacities stay locked until the end of the transaction, a concurrent write waits for them
+    /// # Returns
+    /// * `AppError` - Conflict when a capacity is exceeded under the reject policy,
+    ///   unless the write allows overbooking, with its `owner`, `capacity`, planned `total` and `remaining` hours as fields
+    ///
+    pub fn check_capacity(&self, card: &Card) -> Result<(), AppError> {
+        let default = match self.capacity {
+            Some(capacity) if !self.allow_overbook => capacity,
+            _ => return Ok(()),
+        };
+
+        let policy = self
+            .get_board(card.board_id)?
+            .capacity_policy
+            .unwrap_or(default.policy);
+        if policy == CapacityPolicy::Warn {
+            return Ok(());
+        }
+
+        self.lock_capacities(card)?;
+        match self.overruns(card)?.first() {
+            Some(overrun) => Err(AppError::with_fields(
+                overrun.message(card).as_str(),
+                ErrorType::Conflict,
+                overrun.fields(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{ChecklistItem, ChecklistItemData, ChecklistProgress, NewChecklistItem},
+    errors::AppError,
+};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+impl DBAccessManager {
+    pub fn get_checklist(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+    ) -> Result<Vec<ChecklistItem>, AppError> {
+        use crate::schema::checklist_items::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        checklist_items
+            .filter(card_id.eq(_card_id))
+            .order((position.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving checklist"))
+    }
+
+    fn checklist_length(&self, _card_id: i64) -> Result<i32, AppError> {
+        use crate::schema::checklist_items::dsl::*;
+
+        checklist_items
+            .filter(card_id.eq(_card_id))
+            .count()
+            .get_result::<i64>(&self.connection)
+            .map(|count| count as i32)
+            .map_err(|err| AppError::from_diesel_err(err, "while counting checklist items"))
+    }
+
+    /// Add an item to the checklist of a card, the items after it are shifted down
+    pub fn create_checklist_item(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        dto: ChecklistItemData,
+    ) -> Result<ChecklistItem, AppError> {
+        use crate::schema::checklist_items::dsl::*;
+
+        self.connection.transaction(|| {
+            // Serializes the checklist writes of a card, positions stay contiguous
+            self.lock_card(_board_id, _card_id, None)?;
+
+            let length = self.checklist_length(_card_id)?;
+            let new_position = dto.position.map_or(length, |wanted| wanted.min(length));
+
+            diesel::update(
+                checklist_items
+                    .filter(card_id.eq(_card_id))
+                    .filter(position.ge(new_position)),
+            )
+            .set(position.eq(position + 1))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating checklist item"))?;
+
+            diesel::insert_into(checklist_items)
+                .values(&NewChecklistItem {
+                    card_id: _card_id,
+                    text: &dto.text,
+                    done: dto.done,
+                    position: new_position,
+                })
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while creating checklist item"))
+        })
+    }
+
+    /// Edit an item of a card checklist, moving it when a new position is given
+    pub fn edit_checklist_item(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _id: i64,
+        dto: ChecklistItemData,
+    ) -> Result<ChecklistItem, AppError> {
+        use crate::schema::checklist_items::dsl::*;
+
+        self.connection.transaction(|| {
+            self.lock_card(_board_id, _card_id, None)?;
+
+            let item: ChecklistItem = checklist_items
+                .filter(id.eq(_id))
+                .filter(card_id.eq(_card_id))
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving checklist item"))?;
+
+            let last = self.checklist_length(_card_id)? - 1;
+            let new_position = dto
+                .position
+                .map_or(item.position, |wanted| wanted.min(last));
+            let siblings = checklist_items
+                .filter(card_id.eq(_card_id))
+                .filter(id.ne(_id));
+
+            if new_position < item.position {
+                diesel::update(
+                    siblings
+                        .filter(position.ge(new_position))
+                        .filter(position.lt(item.position)),
+                )
+                .set(position.eq(position + 1))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while moving checklist item"))?;
+            } else if new_position > item.position {
+                diesel::update(
+                    siblings
+                        .filter(position.gt(item.position))
+                        .filter(position.le(new_position)),
+                )
+                .set(position.eq(position - 1))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while moving checklist item"))?;
+            }
+
+            diesel::update(checklist_items.filter(id.eq(_id)))
+                .set((
+                    text.eq(dto.text),
+                    done.eq(dto.done),
+                    position.eq(new_position),
+                ))
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while updating checklist item"))
+        })
+    }
+
+    /// Delete an item of a card checklist, the items after it are shifted up
+    pub fn delete_checklist_item(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _id: i64,
+    ) -> Result<usize, AppError> {
+        use crate::schema::checklist_items::dsl::*;
+
+        self.connection.transaction(|| {
+            self.lock_card(_board_id, _card_id, None)?;
+
+            let item: ChecklistItem = diesel::delete(
+                checklist_items
+                    .filter(id.eq(_id))
+                    .filter(card_id.eq(_card_id)),
+            )
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting checklist item"))?;
+
+            diesel::update(
+                checklist_items
+                    .filter(card_id.eq(_card_id))
+                    .filter(position.gt(item.position)),
+            )
+            .set(position.eq(position - 1))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting checklist item"))?;
+
+            Ok(1)
+        })
+    }
+
+    /// Checklist completion of each card, cards without items are left out
+    pub fn checklist_progress(
+        &self,
+        card_ids: &[i64],
+    ) -> Result<HashMap<i64, ChecklistProgress>, AppError> {
+        use crate::schema::checklist_items::dsl::*;
+
+        let rows: Vec<(i64, bool)> = checklist_items
+            .filter(card_id.eq_any(card_ids))
+            .select((card_id, done))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving checklists"))?;
+
+        let mut progress: HashMap<i64, ChecklistProgress> = HashMap::new();
+        for (card, is_done) in rows {
+            let entry = progress.entry(card).or_default();
+            entry.total += 1;
+            if is_done {
+                entry.done += 1;
+            }
+        }
+
+        Ok(progress)
+    }
+}
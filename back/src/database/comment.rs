@@ -0,0 +1,113 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Comment, CommentData, CommentPage, NewComment, PageQuery},
+    errors::AppError,
+};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+impl DBAccessManager {
+    /// Comments of a live card, oldest first
+    pub fn get_comments(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        query: PageQuery,
+    ) -> Result<CommentPage, AppError> {
+        use crate::schema::comments::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        let total: i64 = comments
+            .filter(card_id.eq(_card_id))
+            .count()
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while counting comments"))?;
+
+        let page = comments
+            .filter(card_id.eq(_card_id))
+            .order((created_at.asc(), id.asc()))
+            .limit(query.per_page())
+            .offset((query.page() - 1) * query.per_page())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving comments"))?;
+
+        Ok(CommentPage {
+            comments: page,
+            page: query.page(),
+            per_page: query.per_page(),
+            total,
+        })
+    }
+
+    /// Comment a live card, signed with the author of the request
+    pub fn create_comment(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        dto: CommentData,
+    ) -> Result<Comment, AppError> {
+        use crate::schema::comments;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::insert_into(comments::table)
+            .values(&NewComment {
+                card_id: _card_id,
+                author: self.author.as_deref(),
+                body: &dto.body,
+            })
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while creating comment"))
+    }
+
+    pub fn edit_comment(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _id: i64,
+        dto: CommentData,
+    ) -> Result<Comment, AppError> {
+        use crate::schema::comments::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::update(comments.filter(id.eq(_id)).filter(card_id.eq(_card_id)))
+            .set(body.eq(dto.body))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while updating comment"))
+    }
+
+    pub fn delete_comment(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _id: i64,
+    ) -> Result<usize, AppError> {
+        use crate::schema::comments::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::delete(comments.filter(id.eq(_id)).filter(card_id.eq(_card_id)))
+            .get_result::<Comment>(&self.connection)
+            .map(|_| 1)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting comment"))
+    }
+
+    /// Number of comments of each card, cards without comments are left out
+    pub fn comment_counts(&self, card_ids: &[i64]) -> Result<HashMap<i64, i64>, AppError> {
+        use crate::schema::comments::dsl::*;
+
+        // Diesel 1.x does not let `count_star` sit next to a grouped column
+        comments
+            .filter(card_id.eq_any(card_ids))
+            .group_by(card_id)
+            .select((
+                card_id,
+                diesel::dsl::sql::<diesel::sql_types::BigInt>("count(*)"),
+            ))
+            .load::<(i64, i64)>(&self.connection)
+            .map(|counts| counts.into_iter().collect())
+            .map_err(|err| AppError::from_diesel_err(err, "while counting comments"))
+    }
+}
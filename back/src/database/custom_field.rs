@@ -0,0 +1,239 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{CustomField, CustomFieldData, CustomFieldFilter, CustomFieldType},
+    errors::{AppError, ErrorType, FieldError},
+    validation::check_custom_value,
+};
+use diesel::{
+    pg::Pg,
+    prelude::*,
+    sql_types::{BigInt, Text},
+};
+use serde_json::{Map, Value};
+
+// Containment of `jsonb` values, for the custom field filters of the card list
+diesel_infix_operator!(JsonbContains, " @> ", backend: Pg);
+
+/// Unique violations on `custom_fields` can only come from the field name
+const CUSTOM_FIELD_NAME_TAKEN: &str = "a custom field with this name already exists on this board";
+
+impl DBAccessManager {
+    pub fn create_custom_field(
+        &self,
+        _board_id: i64,
+        dto: CustomFieldData,
+    ) -> Result<CustomField, AppError> {
+        use crate::schema::custom_fields;
+
+        self.get_board(_board_id)?;
+
+        diesel::insert_into(custom_fields::table)
+            .values((&dto, custom_fields::board_id.eq(_board_id)))
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(
+                    err,
+                    "while creating custom field",
+                    CUSTOM_FIELD_NAME_TAKEN,
+                )
+            })
+    }
+
+    pub fn get_custom_fields(&self, _board_id: i64) -> Result<Vec<CustomField>, AppError> {
+        use crate::schema::custom_fields::dsl::*;
+
+        self.get_board(_board_id)?;
+
+        custom_fields
+            .filter(board_id.eq(_board_id))
+            .order((name.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving custom fields"))
+    }
+
+    /// Change a custom field, the values of the cards follow a rename
+    /// # Returns
+    /// * `AppError` - Conflict when values stored on cards do not fit the new definition
+    ///
+    pub fn edit_custom_field(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        dto: CustomFieldData,
+    ) -> Result<CustomField, AppError> {
+        use crate::schema::{custom_fields, data};
+
+        self.connection.transaction(|| {
+            let previous: CustomField = custom_fields::table
+                .filter(custom_fields::id.eq(_id))
+                .filter(custom_fields::board_id.eq(_board_id))
+                .for_update()
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving custom field"))?;
+
+            let field: CustomField = diesel::update(custom_fields::table.find(previous.id))
+                .set(&dto)
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_unique_violation(err, "while updating custom field", CUSTOM_FIELD_NAME_TAKEN))?;
+
+            let stored: Vec<Value> = data::table
+                .filter(data::board_id.eq(_board_id))
+                .select(data::custom)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving custom values"))?;
+            let mut errors = Vec::new();
+            for value in stored.iter().filter_map(|custom| custom.get(&previous.name)) {
+                check_custom_value(&field, value, &mut errors);
+            }
+            if !errors.is_empty() {
+                return Err(AppError::new(
+                    format!(
+                        "{} card(s) hold values of `{}` the new definition rejects: {}",
+                        errors.len(),
+                        previous.name,
+                        errors[0].message
+                    )
+                    .as_str(),
+                    ErrorType::Conflict,
+                ));
+            }
+
+            if field.name != previous.name {
+                diesel::sql_query(
+                    "UPDATE data SET custom = (custom - $1) || jsonb_build_object($2::text, custom -> $1) \
+                     WHERE board_id = $3 AND custom ? $1",
+                )
+                .bind::<Text, _>(&previous.name)
+                .bind::<Text, _>(&field.name)
+                .bind::<BigInt, _>(_board_id)
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while renaming custom values"))?;
+            }
+
+            Ok(field)
+        })
+    }
+
+    /// Delete a custom field, its values are removed from every card of the board
+    pub fn delete_custom_field(&self, _board_id: i64, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::custom_fields;
+
+        self.connection.transaction(|| {
+            let field: CustomField = custom_fields::table
+                .filter(custom_fields::id.eq(_id))
+                .filter(custom_fields::board_id.eq(_board_id))
+                .first(&self.connection)
+                .optional()
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving custom field"))?
+                .ok_or_else(|| AppError::new("custom field not found", ErrorType::NotFound))?;
+
+            diesel::sql_query(
+                "UPDATE data SET custom = custom - $1 WHERE board_id = $2 AND custom ? $1",
+            )
+            .bind::<Text, _>(&field.name)
+            .bind::<BigInt, _>(_board_id)
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while removing custom values"))?;
+
+            diesel::delete(custom_fields::table.find(field.id))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while deleting custom field"))
+        })
+    }
+
+    /// Check the custom values of a card against the fields of its board
+    /// # Arguments
+    /// * `custom` - Object of the values keyed by field name, `null` values are dropped
+    /// # Returns
+    /// * `Value` - The values to store
+    /// * `AppError` - MissingRequiredField error listing every failing field
+    ///
+    pub fn check_custom_values(&self, _board_id: i64, custom: Value) -> Result<Value, AppError> {
+        let values = match custom {
+            Value::Object(values) => values,
+            _ => {
+                return Err(AppError::with_fields(
+                    "FIELD_ERROR: 1 invalid field(s)",
+                    ErrorType::MissingRequiredField,
+                    vec![FieldError::new("custom", "must be an object")],
+                ))
+            }
+        };
+        let fields = self.get_custom_fields(_board_id)?;
+
+        let mut errors = Vec::new();
+        let mut checked = Map::new();
+        for (name, value) in values.into_iter().filter(|(_, value)| !value.is_null()) {
+            match fields.iter().find(|field| field.name == name) {
+                Some(field) => check_custom_value(field, &value, &mut errors),
+                None => errors.push(FieldError::new(
+                    format!("custom.{}", name).as_str(),
+                    "is not a custom field of this board",
+                )),
+            }
+            checked.insert(name, value);
+        }
+        for field in fields.iter().filter(|field| field.required) {
+            if !checked.contains_key(&field.name) {
+                errors.push(FieldError::new(
+                    format!("custom.{}", field.name).as_str(),
+                    "is required",
+                ));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::with_fields(
+                format!("FIELD_ERROR: {} invalid field(s)", errors.len()).as_str(),
+                ErrorType::MissingRequiredField,
+                errors,
+            ));
+        }
+
+        Ok(Value::Object(checked))
+    }
+
+    /// Values the listed cards must hold, typed as the fields store them
+    /// # Returns
+    /// * `AppError` - BadRequest for an unknown field or a value of the wrong type
+    ///
+    pub fn custom_filter(
+        &self,
+        _board_id: i64,
+        filters: &[CustomFieldFilter],
+    ) -> Result<Value, AppError> {
+        let fields = self.get_custom_fields(_board_id)?;
+
+        let mut values = Map::new();
+        for filter in filters {
+            let field = fields
+                .iter()
+                .find(|field| field.name == filter.name)
+                .ok_or_else(|| {
+                    AppError::new(
+                        format!("`{}` is not a custom field of this board", filter.name).as_str(),
+                        ErrorType::BadRequest,
+                    )
+                })?;
+
+            let value = match field.field_type {
+                CustomFieldType::Number => filter
+                    .value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(|| {
+                        AppError::new(
+                            format!("custom field `{}` holds numbers", field.name).as_str(),
+                            ErrorType::BadRequest,
+                        )
+                    })?,
+                _ => Value::String(filter.value.clone()),
+            };
+            values.insert(field.name.clone(), value);
+        }
+
+        Ok(Value::Object(values))
+    }
+}
@@ -0,0 +1,171 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Card, CardDependency, CardStatus, CardView},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+impl DBAccessManager {
+    /// Make a card of the board wait for another one, adding a link twice is a no-op
+    /// # Arguments
+    /// * `_board_id` - Board both cards must belong to
+    /// * `_card_id` - Id of the blocked card
+    /// * `_blocker_id` - Id of the card it waits for
+    /// # Returns
+    /// * `AppError` - Conflict when the blocker already waits for the card, directly or not
+    ///
+    pub fn add_blocker(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _blocker_id: i64,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::card_dependencies;
+
+        if _card_id == _blocker_id {
+            return Err(AppError::new(
+                "a card cannot block itself",
+                ErrorType::BadRequest,
+            ));
+        }
+
+        let card = self.connection.transaction(|| {
+            // Two links added at once could close a cycle neither of them sees
+            diesel::sql_query("LOCK TABLE card_dependencies IN SHARE ROW EXCLUSIVE MODE")
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while locking dependencies"))?;
+
+            let card = self.get_card_by_id(_board_id, _card_id)?;
+            let blocker = self.get_card_by_id(_board_id, _blocker_id)?;
+
+            if self.blocks(card.id, blocker.id)? {
+                return Err(AppError::new(
+                    format!(
+                        "card {} already waits for card {}, the link would form a cycle",
+                        blocker.id, card.id
+                    )
+                    .as_str(),
+                    ErrorType::Conflict,
+                ));
+            }
+
+            diesel::insert_into(card_dependencies::table)
+                .values(&CardDependency {
+                    blocker_id: blocker.id,
+                    blocked_id: card.id,
+                })
+                .on_conflict_do_nothing()
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while adding blocker"))?;
+
+            Ok(card)
+        })?;
+
+        self.card_view(card)
+    }
+
+    /// Stop a card from waiting for another one, removing a missing link is a no-op
+    pub fn remove_blocker(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _blocker_id: i64,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::card_dependencies::dsl::*;
+
+        let card = self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::delete(
+            card_dependencies
+                .filter(blocked_id.eq(card.id))
+                .filter(blocker_id.eq(_blocker_id)),
+        )
+        .execute(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while removing blocker"))?;
+
+        self.card_view(card)
+    }
+
+    /// Whether `target` waits for `blocker`, directly or through other cards
+    fn blocks(&self, blocker: i64, target: i64) -> Result<bool, AppError> {
+        use crate::schema::card_dependencies::dsl::*;
+
+        let mut seen = HashSet::from([blocker]);
+        let mut frontier = vec![blocker];
+
+        while !frontier.is_empty() {
+            let next: Vec<i64> = card_dependencies
+                .filter(blocker_id.eq_any(&frontier))
+                .select(blocked_id)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving dependencies"))?;
+
+            if next.contains(&target) {
+                return Ok(true);
+            }
+            frontier = next.into_iter().filter(|id| seen.insert(*id)).collect();
+        }
+
+        Ok(false)
+    }
+
+    /// Live blockers of each card with their status, cards without blockers are left out
+    pub fn card_blockers(
+        &self,
+        card_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<(i64, CardStatus)>>, AppError> {
+        use crate::schema::{card_dependencies, data};
+
+        let rows: Vec<(i64, i64, CardStatus)> = card_dependencies::table
+            .inner_join(data::table.on(data::id.eq(card_dependencies::blocker_id)))
+            .filter(card_dependencies::blocked_id.eq_any(card_ids))
+            .filter(data::deleted_at.is_null())
+            .order(card_dependencies::blocker_id.asc())
+            .select((card_dependencies::blocked_id, data::id, data::status))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving blockers"))?;
+
+        let mut blockers: HashMap<i64, Vec<(i64, CardStatus)>> = HashMap::new();
+        for (blocked, blocker, status) in rows {
+            blockers.entry(blocked).or_default().push((blocker, status));
+        }
+
+        Ok(blockers)
+    }
+
+    /// Check a card can be started or completed
+    /// # Arguments
+    /// * `card` - The card, with its current status
+    /// * `to` - Requested status
+    /// # Returns
+    /// * `AppError` - Conflict when one of its blockers is not completed
+    ///
+    pub fn check_blockers(&self, card: &Card, to: CardStatus) -> Result<(), AppError> {
+        if to == card.status || to == CardStatus::Todo {
+            return Ok(());
+        }
+
+        let pending: Vec<String> = self
+            .card_blockers(&[card.id])?
+            .remove(&card.id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, status)| *status != CardStatus::Completed)
+            .map(|(blocker, _)| blocker.to_string())
+            .collect();
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        Err(AppError::new(
+            format!(
+                "card is blocked until these cards are completed: [{}]",
+                pending.join(", ")
+            )
+            .as_str(),
+            ErrorType::Conflict,
+        ))
+    }
+}
@@ -0,0 +1,229 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Card, CardStatus, CardTree, CardView, ChildRollup, RevisionAction},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Key of the advisory lock taken by the writes of the card hierarchy
+const HIERARCHY_LOCK_KEY: i64 = 0x6361_7264_7472_6565;
+
+/// Nest the cards under their parents, from `card` down
+fn build_tree(card: CardView, by_parent: &mut HashMap<i64, Vec<CardView>>) -> CardTree {
+    let children = by_parent.remove(&card.card.id).unwrap_or_default();
+
+    CardTree {
+        card,
+        children: children
+            .into_iter()
+            .map(|child| build_tree(child, by_parent))
+            .collect(),
+    }
+}
+
+impl DBAccessManager {
+    /// Serialize the writes of the card hierarchy until the end of the transaction,
+    /// two cards re-parented at once could close a cycle neither of them sees
+    pub fn lock_hierarchy(&self) -> Result<(), AppError> {
+        diesel::sql_query(format!(
+            "SELECT pg_advisory_xact_lock({})",
+            HIERARCHY_LOCK_KEY
+        ))
+        .execute(&self.connection)
+        .map(|_| ())
+        .map_err(|err| AppError::from_diesel_err(err, "while locking card hierarchy"))
+    }
+
+    /// Whether `ancestor` is `card` itself or one of its ancestors
+    fn is_ancestor(&self, ancestor: i64, card: i64) -> Result<bool, AppError> {
+        use crate::schema::data::dsl::*;
+
+        let mut seen = HashSet::new();
+        let mut current = Some(card);
+        while let Some(card) = current.filter(|card| seen.insert(*card)) {
+            if card == ancestor {
+                return Ok(true);
+            }
+            current = data
+                .filter(id.eq(card))
+                .select(parent_id)
+                .first::<Option<i64>>(&self.connection)
+                .optional()
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving parent"))?
+                .flatten();
+        }
+
+        Ok(false)
+    }
+
+    /// Make a card a child of another card of the board
+    /// # Returns
+    /// * `AppError` - BadRequest for the card itself, Conflict when the parent is one of its descendants,
+    ///   PreconditionFailed when the card version differs from `expected_version`
+    ///
+    pub fn set_parent(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _parent_id: i64,
+        expected_version: Option<i32>,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::data::dsl::*;
+
+        if _card_id == _parent_id {
+            return Err(AppError::new(
+                "a card cannot be its own parent",
+                ErrorType::BadRequest,
+            ));
+        }
+
+        let card = self.connection.transaction(|| {
+            self.lock_hierarchy()?;
+
+            let card = self.lock_card(_board_id, _card_id, expected_version)?;
+            let parent = self.get_card_by_id(_board_id, _parent_id)?;
+
+            if self.is_ancestor(card.id, parent.id)? {
+                return Err(AppError::new(
+                    format!(
+                        "card {} is a descendant of card {}, the link would form a cycle",
+                        parent.id, card.id
+                    )
+                    .as_str(),
+                    ErrorType::Conflict,
+                ));
+            }
+
+            let card: Card = diesel::update(data.filter(id.eq(card.id)))
+                .set(parent_id.eq(parent.id))
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while setting parent"))?;
+
+            self.record_revision(&card, RevisionAction::Update)?;
+            Ok(card)
+        })?;
+
+        self.card_view(card)
+    }
+
+    /// Move a card back to the top level, its own children stay with it
+    /// # Returns
+    /// * `AppError` - PreconditionFailed when the card version differs from `expected_version`
+    ///
+    pub fn remove_parent(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        expected_version: Option<i32>,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::data::dsl::*;
+
+        let card = self.connection.transaction::<_, AppError, _>(|| {
+            let card = self.lock_card(_board_id, _card_id, expected_version)?;
+
+            let card: Card = diesel::update(data.filter(id.eq(card.id)))
+                .set(parent_id.eq(None::<i64>))
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while removing parent"))?;
+
+            self.record_revision(&card, RevisionAction::Update)?;
+            Ok(card)
+        })?;
+
+        self.card_view(card)
+    }
+
+    /// Live children of the given cards, in board order
+    pub fn children_of(&self, card_ids: &[i64]) -> Result<Vec<Card>, AppError> {
+        use crate::schema::data::dsl::*;
+
+        data.filter(parent_id.eq_any(card_ids))
+            .filter(deleted_at.is_null())
+            .order((status.asc(), rank.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving children"))
+    }
+
+    /// Live descendants of a card, level by level
+    pub fn descendants_of(&self, card_id: i64) -> Result<Vec<Card>, AppError> {
+        let mut seen = HashSet::from([card_id]);
+        let mut descendants = Vec::new();
+        let mut frontier = vec![card_id];
+
+        while !frontier.is_empty() {
+            let children: Vec<Card> = self
+                .children_of(&frontier)?
+                .into_iter()
+                .filter(|child| seen.insert(child.id))
+                .collect();
+            frontier = children.iter().map(|child| child.id).collect();
+            descendants.extend(children);
+        }
+
+        Ok(descendants)
+    }
+
+    pub fn get_children(&self, _board_id: i64, _card_id: i64) -> Result<Vec<CardView>, AppError> {
+        let card = self.get_card_by_id(_board_id, _card_id)?;
+
+        self.card_views(self.children_of(&[card.id])?)
+    }
+
+    /// A card with its live descendants, nested under their parents
+    pub fn get_subtree(&self, _board_id: i64, _card_id: i64) -> Result<CardTree, AppError> {
+        let root = self.get_card_by_id(_board_id, _card_id)?;
+        let root_id = root.id;
+
+        let mut cards = vec![root];
+        cards.extend(self.descendants_of(root_id)?);
+
+        let mut root = None;
+        let mut by_parent: HashMap<i64, Vec<CardView>> = HashMap::new();
+        for view in self.card_views(cards)? {
+            if view.card.id == root_id {
+                root = Some(view);
+            } else if let Some(parent) = view.card.parent_id {
+                by_parent.entry(parent).or_default().push(view);
+            }
+        }
+
+        root.map(|root| build_tree(root, &mut by_parent))
+            .ok_or_else(|| AppError::new("card not found", ErrorType::NotFound))
+    }
+
+    /// Rolled up fields of the live children of each card, cards without children are left out
+    pub fn child_rollups(&self, card_ids: &[i64]) -> Result<HashMap<i64, ChildRollup>, AppError> {
+        use crate::schema::data::dsl::*;
+
+        let rows: Vec<(Option<i64>, i32, CardStatus)> = data
+            .filter(parent_id.eq_any(card_ids))
+            .filter(deleted_at.is_null())
+            .select((parent_id, duration, status))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving children"))?;
+
+        let mut rollups: HashMap<i64, (ChildRollup, i64)> = HashMap::new();
+        for (parent, child_duration, child_status) in rows {
+            let (rollup, completed) = rollups.entry(parent.unwrap_or_default()).or_default();
+            rollup.count += 1;
+            rollup.duration += i64::from(child_duration);
+            if child_status == CardStatus::Completed {
+                *completed += 1;
+            }
+        }
+
+        Ok(rollups
+            .into_iter()
+            .map(|(parent, (rollup, completed))| {
+                (
+                    parent,
+                    ChildRollup {
+                        completed_percent: completed * 100 / rollup.count,
+                        ..rollup
+                    },
+                )
+            })
+            .collect())
+    }
+}
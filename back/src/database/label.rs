@@ -0,0 +1,114 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{CardLabel, CardView, Label, LabelData},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+
+/// Unique violations on `labels` can only come from the label name
+const LABEL_NAME_TAKEN: &str = "a label with this name already exists on this board";
+
+impl DBAccessManager {
+    pub fn create_label(&self, _board_id: i64, dto: LabelData) -> Result<Label, AppError> {
+        use crate::schema::labels;
+
+        self.get_board(_board_id)?;
+
+        diesel::insert_into(labels::table)
+            .values((&dto, labels::board_id.eq(_board_id)))
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while creating label", LABEL_NAME_TAKEN)
+            })
+    }
+
+    pub fn get_labels(&self, _board_id: i64) -> Result<Vec<Label>, AppError> {
+        use crate::schema::labels::dsl::*;
+
+        self.get_board(_board_id)?;
+
+        labels
+            .filter(board_id.eq(_board_id))
+            .order((name.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving labels"))
+    }
+
+    pub fn edit_label(&self, _board_id: i64, _id: i64, dto: LabelData) -> Result<Label, AppError> {
+        use crate::schema::labels::dsl::*;
+
+        diesel::update(labels.filter(id.eq(_id)).filter(board_id.eq(_board_id)))
+            .set(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while updating label", LABEL_NAME_TAKEN)
+            })
+    }
+
+    /// Delete a label, it is detached from every card
+    pub fn delete_label(&self, _board_id: i64, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::labels::dsl::*;
+
+        let deleted = diesel::delete(labels.filter(id.eq(_id)).filter(board_id.eq(_board_id)))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting label"))?;
+
+        if deleted == 0 {
+            return Err(AppError::new("label not found", ErrorType::NotFound));
+        }
+
+        Ok(deleted)
+    }
+
+    /// Attach a label of the board to one of its cards, attaching it twice is a no-op
+    pub fn attach_label(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _label_id: i64,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::{card_labels, labels};
+
+        let card = self.get_card_by_id(_board_id, _card_id)?;
+
+        labels::table
+            .filter(labels::id.eq(_label_id))
+            .filter(labels::board_id.eq(_board_id))
+            .select(labels::id)
+            .first::<i64>(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving label"))?;
+
+        diesel::insert_into(card_labels::table)
+            .values(&CardLabel {
+                card_id: card.id,
+                label_id: _label_id,
+            })
+            .on_conflict_do_nothing()
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while attaching label"))?;
+
+        self.card_view(card)
+    }
+
+    /// Detach a label from a card, detaching a label the card does not carry is a no-op
+    pub fn detach_label(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _label_id: i64,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::card_labels::dsl::*;
+
+        let card = self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::delete(
+            card_labels
+                .filter(card_id.eq(card.id))
+                .filter(label_id.eq(_label_id)),
+        )
+        .execute(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while detaching label"))?;
+
+        self.card_view(card)
+    }
+}
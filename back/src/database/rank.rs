@@ -0,0 +1,275 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Card, CardMove, CardStatus, RevisionAction},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+
+/// Digits of a rank, in ascending order, ranks compare as plain byte strings
+const RANK_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Rank sorting strictly between two ranks, `None` standing for the start or the end of a column.
+/// Generated ranks never end with the lowest digit, so there is always room before them.
+/// # Returns
+/// * `None` - `before` does not sort before `after`
+///
+pub fn rank_between(before: Option<&str>, after: Option<&str>) -> Option<String> {
+    let base = RANK_DIGITS.len();
+    let digit = |rank: &[u8], index: usize| {
+        rank.get(index)
+            .and_then(|c| RANK_DIGITS.iter().position(|d| d == c))
+            .unwrap_or(0)
+    };
+
+    let before = before.unwrap_or_default().as_bytes();
+    let mut after = after.map(str::as_bytes);
+    if matches!(after, Some(after) if before >= after) {
+        return None;
+    }
+
+    // Appending only bumps a digit, halving the space would make ranks grow at the end of a column
+    let appending = after.is_none();
+    let mut rank = String::new();
+
+    for index in 0.. {
+        let low = digit(before, index);
+        let high = match after {
+            Some(after) => digit(after, index),
+            None if appending && low + 1 < base => {
+                rank.push(RANK_DIGITS[low + 1] as char);
+                break;
+            }
+            None => base,
+        };
+
+        if high > low + 1 {
+            rank.push(RANK_DIGITS[(low + high) / 2] as char);
+            break;
+        }
+
+        rank.push(RANK_DIGITS[low] as char);
+        if high == low + 1 {
+            // Any suffix of the lower digit now sorts before `after`
+            after = None;
+        }
+    }
+
+    Some(rank)
+}
+
+impl DBAccessManager {
+    /// Rank putting a card at the end of a column, trashed cards keep their place when restored
+    pub fn end_of_column_rank(
+        &self,
+        _board_id: i64,
+        _status: CardStatus,
+    ) -> Result<String, AppError> {
+        use crate::schema::data::dsl::*;
+
+        let last: Option<String> = data
+            .filter(board_id.eq(_board_id))
+            .filter(status.eq(_status))
+            .select(diesel::dsl::max(rank))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving column ranks"))?;
+
+        Ok(rank_between(last.as_deref(), None).unwrap_or_default())
+    }
+
+    /// Rank of a live card that will sit next to `_moved` in a column
+    fn neighbour_rank(
+        &self,
+        _board_id: i64,
+        _moved: i64,
+        _neighbour: i64,
+        _status: CardStatus,
+    ) -> Result<String, AppError> {
+        use crate::schema::data::dsl::*;
+
+        data.filter(id.eq(_neighbour))
+            .filter(id.ne(_moved))
+            .filter(board_id.eq(_board_id))
+            .filter(status.eq(_status))
+            .filter(deleted_at.is_null())
+            .select(rank)
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving neighbour card"))?
+            .ok_or_else(|| {
+                AppError::new(
+                    format!(
+                        "card {} is not in the `{}` column of this board",
+                        _neighbour, _status
+                    )
+                    .as_str(),
+                    ErrorType::BadRequest,
+                )
+            })
+    }
+
+    /// Closest rank of a live card of the column, below or above `_rank`
+    fn adjacent_rank(
+        &self,
+        _board_id: i64,
+        _moved: i64,
+        _status: CardStatus,
+        _rank: Option<&str>,
+        above: bool,
+    ) -> Result<Option<String>, AppError> {
+        use crate::schema::data::dsl::*;
+
+        let mut statement = data
+            .filter(id.ne(_moved))
+            .filter(board_id.eq(_board_id))
+            .filter(status.eq(_status))
+            .filter(deleted_at.is_null())
+            .select(rank)
+            .into_boxed();
+
+        statement = match (_rank, above) {
+            (Some(_rank), true) => statement.filter(rank.gt(_rank.to_string())),
+            (Some(_rank), false) => statement.filter(rank.lt(_rank.to_string())),
+            (None, _) => statement,
+        };
+        statement = if above {
+            statement.order(rank.asc())
+        } else {
+            statement.order(rank.desc())
+        };
+
+        statement
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving column ranks"))
+    }
+
+    /// Move a card to a position of a column, only the moved card is written
+    /// # Arguments
+    /// * `_board_id` - Board the card must belong to
+    /// * `_id` - Id of the card
+    /// * `dto` - Target column and neighbours
+    /// * `expected_version` - Version sent in `If-Match`, `None` skips the check
+    /// # Returns
+    /// * `AppError` - BadRequest when a neighbour is not in the target column or they are not in order
+    ///
+    pub fn move_card(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        dto: CardMove,
+        expected_version: Option<i32>,
+    ) -> Result<Card, AppError> {
+        use crate::schema::data::dsl::*;
+
+        self.connection.transaction(|| {
+            let previous = self.lock_card(_board_id, _id, expected_version)?;
+            self.check_status_change(_board_id, Some(previous.status), dto.status)?;
+            self.check_blockers(&previous, dto.status)?;
+            self.check_wip_limit(_board_id, Some(&previous), dto.status)?;
+
+            let before = match dto.before {
+                Some(before) => Some(self.neighbour_rank(_board_id, _id, before, dto.status)?),
+                None => None,
+            };
+            let after = match dto.after {
+                Some(after) => Some(self.neighbour_rank(_board_id, _id, after, dto.status)?),
+                None => None,
+            };
+
+            // A single neighbour is completed with the card currently next to it
+            let (before, after) = match (before, after) {
+                (Some(before), None) => {
+                    let after =
+                        self.adjacent_rank(_board_id, _id, dto.status, Some(&before), true)?;
+                    (Some(before), after)
+                }
+                (None, Some(after)) => {
+                    let before =
+                        self.adjacent_rank(_board_id, _id, dto.status, Some(&after), false)?;
+                    (before, Some(after))
+                }
+                (None, None) => (
+                    self.adjacent_rank(_board_id, _id, dto.status, None, false)?,
+                    None,
+                ),
+                neighbours => neighbours,
+            };
+
+            let new_rank = rank_between(before.as_deref(), after.as_deref()).ok_or_else(|| {
+                AppError::new(
+                    "`before` must be above `after` in the column",
+                    ErrorType::BadRequest,
+                )
+            })?;
+
+            let card: Card = diesel::update(data.filter(id.eq(_id)))
+                .set((status.eq(dto.status), rank.eq(new_rank)))
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while moving card"))?;
+
+            if card.version != previous.version {
+                self.record_revision(&card, RevisionAction::Update)?;
+            }
+            Ok(card)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sorts strictly between its bounds and leaves room before itself
+    fn assert_between(before: Option<&str>, after: Option<&str>) -> String {
+        let rank = rank_between(before, after).unwrap();
+
+        if let Some(before) = before {
+            assert!(before < rank.as_str(), "{} < {}", before, rank);
+        }
+        if let Some(after) = after {
+            assert!(rank.as_str() < after, "{} < {}", rank, after);
+        }
+        assert!(!rank.ends_with('0'), "{} ends with the lowest digit", rank);
+        rank
+    }
+
+    #[test]
+    fn appending_bumps_the_last_digit() {
+        assert_eq!(rank_between(None, None).as_deref(), Some("1"));
+        assert_eq!(rank_between(Some("1"), None).as_deref(), Some("2"));
+        assert_eq!(rank_between(Some("z"), None).as_deref(), Some("z1"));
+    }
+
+    #[test]
+    fn between_takes_the_middle_digit() {
+        assert_eq!(rank_between(Some("a"), Some("c")).as_deref(), Some("b"));
+        assert_eq!(rank_between(None, Some("1")).as_deref(), Some("0i"));
+        assert_eq!(rank_between(Some("a"), Some("b")).as_deref(), Some("ai"));
+    }
+
+    #[test]
+    fn bounds_out_of_order_have_no_rank() {
+        assert_eq!(rank_between(Some("b"), Some("a")), None);
+        assert_eq!(rank_between(Some("b"), Some("b")), None);
+    }
+
+    #[test]
+    fn repeated_inserts_keep_the_order() {
+        let mut ranks = vec![assert_between(None, None)];
+
+        for _ in 0..50 {
+            let first = assert_between(None, Some(&ranks[0]));
+            ranks.insert(0, first);
+            let last = assert_between(ranks.last().map(String::as_str), None);
+            ranks.push(last);
+            let middle = ranks.len() / 2;
+            let between = assert_between(Some(&ranks[middle - 1]), Some(&ranks[middle]));
+            ranks.insert(middle, between);
+        }
+
+        let mut sorted = ranks.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, ranks);
+    }
+}
@@ -0,0 +1,272 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Card, CardAssignee, CardData, CardLabel, Recurrence, RecurrenceData, RevisionAction},
+    errors::{AppError, ErrorType},
+    rrule::RecurrenceRule,
+};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use diesel::prelude::*;
+
+/// Due time of an occurrence, at the local time of day the template is due
+fn due_on(due_at: DateTime<Utc>, zone: &Tz, day: NaiveDate) -> Option<DateTime<Utc>> {
+    zone.from_local_datetime(&day.and_time(due_at.with_timezone(zone).time()))
+        .earliest()
+        .map(|due_at| due_at.with_timezone(&Utc))
+}
+
+impl DBAccessManager {
+    /// Recurrence of a live card of the board
+    pub fn get_recurrence(&self, _board_id: i64, _card_id: i64) -> Result<Recurrence, AppError> {
+        use crate::schema::recurrences::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        recurrences
+            .filter(card_id.eq(_card_id))
+            .filter(deleted_at.is_null())
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving recurrence"))
+    }
+
+    /// Make a card repeat, or change the rule it repeats on
+    /// Occurrences already generated are kept, the new rule applies after them,
+    /// after the ones of a stopped recurrence too
+    pub fn set_recurrence(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        dto: RecurrenceData,
+    ) -> Result<Recurrence, AppError> {
+        use crate::schema::recurrences::dsl::*;
+
+        let parsed: RecurrenceRule = dto
+            .rule
+            .parse()
+            .map_err(|err: String| AppError::new(err.as_str(), ErrorType::BadRequest))?;
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::insert_into(recurrences)
+            .values((card_id.eq(_card_id), rule.eq(parsed.to_string())))
+            .on_conflict(card_id)
+            .do_update()
+            .set((
+                rule.eq(parsed.to_string()),
+                deleted_at.eq(None::<DateTime<Utc>>),
+            ))
+            .get_result(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while saving recurrence"))
+    }
+
+    /// Stop a card from repeating, the occurrences already generated are kept
+    /// The recurrence is only marked deleted: its occurrences stay linked to it, and setting a rule
+    /// again resumes after the dates it generated instead of generating them twice
+    pub fn delete_recurrence(&self, _board_id: i64, _card_id: i64) -> Result<usize, AppError> {
+        use crate::schema::recurrences::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        let deleted = diesel::update(
+            recurrences
+                .filter(card_id.eq(_card_id))
+                .filter(deleted_at.is_null()),
+        )
+        .set(deleted_at.eq(diesel::dsl::now))
+        .execute(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while deleting recurrence"))?;
+
+        if deleted == 0 {
+            return Err(AppError::new("card does not repeat", ErrorType::NotFound));
+        }
+
+        Ok(deleted)
+    }
+
+    /// Generate the occurrences of every live template card, from `today` to `horizon`
+    /// # Returns
+    /// * `usize` - Number of cards created, recurrences failing to generate left out
+    /// * `AppError` - Internal when the recurrences cannot be listed
+    ///
+    pub fn materialise_recurrences(
+        &self,
+        today: NaiveDate,
+        horizon: NaiveDate,
+    ) -> Result<usize, AppError> {
+        use crate::schema::{data, recurrences};
+
+        let ids: Vec<i64> = recurrences::table
+            .inner_join(data::table)
+            .filter(data::deleted_at.is_null())
+            .filter(recurrences::deleted_at.is_null())
+            .filter(
+                recurrences::generated_until
+                    .is_null()
+                    .or(recurrences::generated_until.lt(horizon)),
+            )
+            .select(recurrences::id)
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving recurrences"))?;
+
+        // Each recurrence runs in its own transaction, one failing is logged and the others still run
+        let mut created = 0;
+        for id in ids {
+            match self.materialise_recurrence(id, today, horizon) {
+                Ok(count) => created += count,
+                Err(err) => log::error!("Error while generating recurrence {}: {}", id, err),
+            }
+        }
+        Ok(created)
+    }
+
+    /// Generate the missing occurrences of one recurrence
+    /// The recurrence is locked and each date is unique per recurrence,
+    /// so concurrent or repeated runs never create an occurrence twice
+    fn materialise_recurrence(
+        &self,
+        _id: i64,
+        today: NaiveDate,
+        horizon: NaiveDate,
+    ) -> Result<usize, AppError> {
+        use crate::schema::{card_assignees, card_labels, data, recurrences};
+
+        self.connection.transaction(|| {
+            let recurrence: Recurrence = recurrences::table
+                .filter(recurrences::id.eq(_id))
+                .for_update()
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while locking recurrence"))?;
+            // Stopped since it was listed
+            if recurrence.deleted_at.is_some() {
+                return Ok(0);
+            }
+
+            let template: Card = data::table
+                .filter(data::id.eq(recurrence.card_id))
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving template card"))?;
+
+            let rule: RecurrenceRule = recurrence.rule.parse().map_err(|err: String| {
+                AppError::new(
+                    format!("recurrence {} has an invalid rule: {}", recurrence.id, err).as_str(),
+                    ErrorType::Internal,
+                )
+            })?;
+
+            // Only upcoming occurrences are generated, the template is the first one
+            let dates: Vec<NaiveDate> = rule
+                .occurrences(template.date, horizon)
+                .into_iter()
+                .filter(|day| *day > template.date && *day >= today)
+                .filter(|day| recurrence.generated_until.is_none_or(|until| *day > until))
+                .collect();
+
+            let zone = template
+                .time_zone
+                .as_deref()
+                .and_then(|zone| zone.parse::<Tz>().ok())
+                .unwrap_or(Tz::UTC);
+            let status = self
+                .get_workflow(template.board_id)?
+                .columns
+                .first()
+                .map_or(template.status, |column| column.status);
+            let label_ids: Vec<i64> = card_labels::table
+                .filter(card_labels::card_id.eq(template.id))
+                .select(card_labels::label_id)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving card labels"))?;
+            let user_ids: Vec<i64> = card_assignees::table
+                .filter(card_assignees::card_id.eq(template.id))
+                .select(card_assignees::user_id)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving card assignees"))?;
+
+            let mut created = 0;
+            for day in dates {
+                // A full column skips the occurrence, as it would refuse a card created by hand
+                match self.check_wip_limit(template.board_id, None, status) {
+                    Err(err) if matches!(err.err_type, ErrorType::Conflict) => {
+                        log::warn!(
+                            "Occurrence of card {} on {} skipped: {}",
+                            template.id,
+                            day,
+                            err
+                        );
+                        continue;
+                    }
+                    result => result?,
+                }
+
+                let rank = self.end_of_column_rank(template.board_id, status)?;
+                let occurrence = CardData {
+                    title: template.title.clone(),
+                    description: template.description.clone(),
+                    date: day,
+                    priority: template.priority,
+                    duration: template.duration,
+                    status,
+                    due_at: template
+                        .due_at
+                        .and_then(|due_at| due_on(due_at, &zone, day)),
+                    time_zone: template.time_zone.clone(),
+                    custom: Some(template.custom.clone()),
+                };
+
+                let card: Option<Card> = diesel::insert_into(data::table)
+                    .values((
+                        &occurrence,
+                        data::board_id.eq(template.board_id),
+                        data::rank.eq(rank),
+                        data::recurrence_id.eq(recurrence.id),
+                    ))
+                    .on_conflict_do_nothing()
+                    .get_result(&self.connection)
+                    .optional()
+                    .map_err(|err| AppError::from_diesel_err(err, "while creating occurrence"))?;
+
+                let card = match card {
+                    Some(card) => card,
+                    None => continue,
+                };
+
+                let labels: Vec<CardLabel> = label_ids
+                    .iter()
+                    .map(|label_id| CardLabel {
+                        card_id: card.id,
+                        label_id: *label_id,
+                    })
+                    .collect();
+                if !labels.is_empty() {
+                    diesel::insert_into(card_labels::table)
+                        .values(&labels)
+                        .execute(&self.connection)
+                        .map_err(|err| AppError::from_diesel_err(err, "while attaching labels"))?;
+                }
+
+                let assignees: Vec<CardAssignee> = user_ids
+                    .iter()
+                    .map(|user_id| CardAssignee {
+                        card_id: card.id,
+                        user_id: *user_id,
+                    })
+                    .collect();
+                if !assignees.is_empty() {
+                    diesel::insert_into(card_assignees::table)
+                        .values(&assignees)
+                        .execute(&self.connection)
+                        .map_err(|err| AppError::from_diesel_err(err, "while assigning users"))?;
+                }
+
+                self.record_revision(&card, RevisionAction::Create)?;
+                created += 1;
+            }
+
+            diesel::update(recurrences::table.filter(recurrences::id.eq(recurrence.id)))
+                .set(recurrences::generated_until.eq(horizon))
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while updating recurrence"))?;
+
+            Ok(created)
+        })
+    }
+}
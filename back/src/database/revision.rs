@@ -0,0 +1,193 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{
+        Card, CardHistoryEntry, CardRevision, NewCardRevision, RevisionAction, RevisionSnapshot,
+    },
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+
+/// Snapshot fields compared between two revisions
+const TRACKED_FIELDS: [&str; 12] = [
+    "title",
+    "description",
+    "date",
+    "priority",
+    "duration",
+    "status",
+    "rank",
+    "deleted_at",
+    "due_at",
+    "time_zone",
+    "custom",
+    "parent_id",
+];
+
+/// Fields that differ between two snapshots, every present field for the first one
+fn changed_fields(
+    previous: Option<&serde_json::Value>,
+    current: &serde_json::Value,
+) -> Vec<String> {
+    // A missing field, an explicit null and no custom values all mean the field is unset
+    let value = |snapshot: &serde_json::Value, field: &str| {
+        snapshot
+            .get(field)
+            .cloned()
+            .filter(|value| !value.is_null() && *value != serde_json::json!({}))
+    };
+
+    TRACKED_FIELDS
+        .iter()
+        .filter(|field| match previous {
+            Some(previous) => value(previous, field) != value(current, field),
+            None => value(current, field).is_some(),
+        })
+        .map(|field| field.to_string())
+        .collect()
+}
+
+impl DBAccessManager {
+    /// Store a snapshot of a card right after it was written
+    pub fn record_revision(&self, card: &Card, action: RevisionAction) -> Result<(), AppError> {
+        use crate::schema::card_revisions;
+
+        let snapshot = serde_json::to_value(card).map_err(|err| {
+            AppError::new(
+                format!("while serializing card revision: {}", err).as_str(),
+                ErrorType::Internal,
+            )
+        })?;
+
+        diesel::insert_into(card_revisions::table)
+            .values(&NewCardRevision {
+                card_id: card.id,
+                version: card.version,
+                action: action.as_str(),
+                author: self.author.as_deref(),
+                snapshot,
+            })
+            .execute(&self.connection)
+            .map(|_| ())
+            .map_err(|err| AppError::from_diesel_err(err, "while recording card revision"))
+    }
+
+    /// History of a card, most recent revision first
+    pub fn get_card_history(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+    ) -> Result<Vec<CardHistoryEntry>, AppError> {
+        use crate::schema::{card_revisions::dsl::*, data};
+
+        // Trashed cards keep their history, only the board is checked
+        data::table
+            .filter(data::id.eq(_card_id))
+            .filter(data::board_id.eq(_board_id))
+            .select(data::id)
+            .first::<i64>(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving card"))?;
+
+        let revisions: Vec<CardRevision> = card_revisions
+            .filter(card_id.eq(_card_id))
+            .order(version.asc())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving card history"))?;
+
+        if revisions.is_empty() {
+            return Err(AppError::new("card not found", ErrorType::NotFound));
+        }
+
+        let mut history = Vec::with_capacity(revisions.len());
+        let mut previous: Option<serde_json::Value> = None;
+
+        for revision in revisions {
+            let fields = changed_fields(previous.as_ref(), &revision.snapshot);
+            previous = Some(revision.snapshot.clone());
+            history.push(CardHistoryEntry {
+                revision,
+                changed_fields: fields,
+            });
+        }
+
+        history.reverse();
+        Ok(history)
+    }
+
+    /// Write back the fields a card had at a given revision
+    /// # Arguments
+    /// * `_board_id` - Board the card must belong to
+    /// * `_id` - Id of the card
+    /// * `_revision` - Version of the card to go back to
+    /// * `expected_version` - Version sent in `If-Match`, `None` skips the check
+    ///
+    pub fn revert_card(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        _revision: i32,
+        expected_version: Option<i32>,
+    ) -> Result<Card, AppError> {
+        use crate::schema::{card_revisions, data::dsl::*};
+
+        self.connection.transaction(|| {
+            let current = self.lock_card(_board_id, _id, expected_version)?;
+
+            let revision: CardRevision = card_revisions::table
+                .filter(card_revisions::card_id.eq(_id))
+                .filter(card_revisions::version.eq(_revision))
+                .first(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving revision"))?;
+
+            let target: RevisionSnapshot =
+                serde_json::from_value(revision.snapshot).map_err(|err| {
+                    AppError::new(
+                        format!("while reading revision {}: {}", _revision, err).as_str(),
+                        ErrorType::Internal,
+                    )
+                })?;
+
+            self.check_status_change(_board_id, Some(current.status), target.status)?;
+            self.check_blockers(&current, target.status)?;
+            self.check_wip_limit(_board_id, Some(&current), target.status)?;
+            let new_rank = self.status_change_rank(_board_id, &current, target.status)?;
+            // Fields may have been deleted, retyped or made required since the snapshot,
+            // snapshots taken before custom fields existed keep the current values
+            let values = match target.custom {
+                values @ serde_json::Value::Object(_) => {
+                    Some(self.check_custom_values(_board_id, values)?)
+                }
+                _ => None,
+            };
+
+            let card: Card = diesel::update(data.filter(id.eq(_id)))
+                .set((
+                    new_rank.map(|new_rank| rank.eq(new_rank)),
+                    title.eq(target.title),
+                    description.eq(target.description),
+                    date.eq(target.date),
+                    priority.eq(target.priority),
+                    duration.eq(target.duration),
+                    status.eq(target.status),
+                    due_at.eq(target.due_at),
+                    time_zone.eq(target.time_zone),
+                    values.map(|values| custom.eq(values)),
+                ))
+                .get_result(&self.connection)
+                .map_err(|err| {
+                    AppError::from_unique_violation(
+                        err,
+                        "while reverting card",
+                        super::OCCURRENCE_DATE_TAKEN,
+                    )
+                })?;
+
+            if card.date != current.date || card.duration != current.duration {
+                self.check_capacity(&card)?;
+            }
+            if card.version != current.version {
+                self.record_revision(&card, RevisionAction::Revert)?;
+            }
+            Ok(card)
+        })
+    }
+}
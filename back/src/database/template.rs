@@ -0,0 +1,175 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{CardData, CardLabel, CardTemplate, CardTemplateData, CardView, NewChecklistItem},
+    errors::{AppError, ErrorType},
+};
+use diesel::prelude::*;
+
+/// Unique violations on `card_templates` can only come from the template name
+const TEMPLATE_NAME_TAKEN: &str = "a template with this name already exists on this board";
+
+impl DBAccessManager {
+    /// Check the labels of a template belong to the board
+    fn check_template_labels(&self, _board_id: i64, label_ids: &[i64]) -> Result<(), AppError> {
+        use crate::schema::labels;
+
+        let found: Vec<i64> = labels::table
+            .filter(labels::id.eq_any(label_ids))
+            .filter(labels::board_id.eq(_board_id))
+            .select(labels::id)
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving labels"))?;
+
+        match label_ids.iter().find(|label_id| !found.contains(label_id)) {
+            Some(missing) => Err(AppError::new(
+                format!("label {} does not belong to this board", missing).as_str(),
+                ErrorType::BadRequest,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn create_template(
+        &self,
+        _board_id: i64,
+        dto: CardTemplateData,
+    ) -> Result<CardTemplate, AppError> {
+        use crate::schema::card_templates;
+
+        self.get_board(_board_id)?;
+        self.check_template_labels(_board_id, &dto.label_ids)?;
+
+        diesel::insert_into(card_templates::table)
+            .values((&dto, card_templates::board_id.eq(_board_id)))
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while creating template", TEMPLATE_NAME_TAKEN)
+            })
+    }
+
+    pub fn get_templates(&self, _board_id: i64) -> Result<Vec<CardTemplate>, AppError> {
+        use crate::schema::card_templates::dsl::*;
+
+        self.get_board(_board_id)?;
+
+        card_templates
+            .filter(board_id.eq(_board_id))
+            .order((name.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving templates"))
+    }
+
+    pub fn get_template(&self, _board_id: i64, _id: i64) -> Result<CardTemplate, AppError> {
+        use crate::schema::card_templates::dsl::*;
+
+        card_templates
+            .filter(id.eq(_id))
+            .filter(board_id.eq(_board_id))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving template"))
+    }
+
+    pub fn edit_template(
+        &self,
+        _board_id: i64,
+        _id: i64,
+        dto: CardTemplateData,
+    ) -> Result<CardTemplate, AppError> {
+        use crate::schema::card_templates::dsl::*;
+
+        self.check_template_labels(_board_id, &dto.label_ids)?;
+
+        diesel::update(
+            card_templates
+                .filter(id.eq(_id))
+                .filter(board_id.eq(_board_id)),
+        )
+        .set(&dto)
+        .get_result(&self.connection)
+        .map_err(|err| {
+            AppError::from_unique_violation(err, "while updating template", TEMPLATE_NAME_TAKEN)
+        })
+    }
+
+    /// Delete a template, the cards created from it are kept
+    pub fn delete_template(&self, _board_id: i64, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::card_templates::dsl::*;
+
+        let deleted = diesel::delete(
+            card_templates
+                .filter(id.eq(_id))
+                .filter(board_id.eq(_board_id)),
+        )
+        .execute(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while deleting template"))?;
+
+        if deleted == 0 {
+            return Err(AppError::new("template not found", ErrorType::NotFound));
+        }
+
+        Ok(deleted)
+    }
+
+    /// Create a card from a template, with its checklist and the labels still on the board
+    /// # Arguments
+    /// * `_board_id` - Board the card is created on
+    /// * `template` - The template, for its labels
+    /// * `dto` - Card of the template, its placeholders resolved
+    /// * `checklist` - Checklist of the template, its placeholders resolved
+    ///
+    pub fn create_card_from_template(
+        &self,
+        _board_id: i64,
+        template: &CardTemplate,
+        dto: CardData,
+        checklist: Vec<String>,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::{card_labels, checklist_items, labels};
+
+        self.connection.transaction(|| {
+            let card = self.create_card(_board_id, dto)?;
+
+            let items: Vec<NewChecklistItem> = checklist
+                .iter()
+                .enumerate()
+                .map(|(position, text)| NewChecklistItem {
+                    card_id: card.id,
+                    text,
+                    done: false,
+                    position: position as i32,
+                })
+                .collect();
+            if !items.is_empty() {
+                diesel::insert_into(checklist_items::table)
+                    .values(&items)
+                    .execute(&self.connection)
+                    .map_err(|err| {
+                        AppError::from_diesel_err(err, "while creating checklist items")
+                    })?;
+            }
+
+            // Labels deleted since the template was saved are skipped
+            let label_ids: Vec<i64> = labels::table
+                .filter(labels::id.eq_any(&template.label_ids))
+                .filter(labels::board_id.eq(_board_id))
+                .select(labels::id)
+                .load(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving labels"))?;
+            let card_labels: Vec<CardLabel> = label_ids
+                .into_iter()
+                .map(|label_id| CardLabel {
+                    card_id: card.id,
+                    label_id,
+                })
+                .collect();
+            if !card_labels.is_empty() {
+                diesel::insert_into(card_labels::table)
+                    .values(&card_labels)
+                    .execute(&self.connection)
+                    .map_err(|err| AppError::from_diesel_err(err, "while attaching labels"))?;
+            }
+
+            self.card_view(card)
+        })
+    }
+}
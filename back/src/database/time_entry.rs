@@ -0,0 +1,200 @@
+use super::DBAccessManager;
+use crate::domain::{
+    day_bounds, local_date, Card, CardEffort, DateRangeQuery, EffortReport, TimeEntry, TimerData,
+};
+use crate::errors::{AppError, ErrorType};
+use chrono::Utc;
+use chrono_tz::Tz;
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+/// The running timers index is the only unique constraint of `time_entries`
+const TIMER_RUNNING: &str = "the user already has a running timer";
+
+impl DBAccessManager {
+    /// Time entries of a live card of the board, the oldest first
+    pub fn get_time_entries(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+    ) -> Result<Vec<TimeEntry>, AppError> {
+        use crate::schema::time_entries::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        time_entries
+            .filter(card_id.eq(_card_id))
+            .order((started_at.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving time entries"))
+    }
+
+    /// Start tracking the time a user spends on a card
+    /// # Returns
+    /// * `AppError` - Conflict when the user already has a running timer, on any card
+    ///
+    pub fn start_timer(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        dto: TimerData,
+    ) -> Result<TimeEntry, AppError> {
+        use crate::schema::time_entries::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+        self.get_user(dto.user_id)?;
+
+        let running: Option<i64> = time_entries
+            .filter(user_id.eq(dto.user_id))
+            .filter(ended_at.is_null())
+            .select(card_id)
+            .first(&self.connection)
+            .optional()
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving running timer"))?;
+        if let Some(running) = running {
+            return Err(AppError::new(
+                format!("the user already has a running timer on card {}", running).as_str(),
+                ErrorType::Conflict,
+            ));
+        }
+
+        // A concurrent start is caught by the unique index on running timers
+        diesel::insert_into(time_entries)
+            .values((
+                card_id.eq(_card_id),
+                user_id.eq(dto.user_id),
+                note.eq(dto.note.unwrap_or_default()),
+            ))
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while starting timer", TIMER_RUNNING)
+            })
+    }
+
+    /// Stop the running timer of a user on a card, a note replaces the one given at start
+    /// # Returns
+    /// * `AppError` - NotFound when the user has no running timer on the card
+    ///
+    pub fn stop_timer(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        dto: TimerData,
+    ) -> Result<TimeEntry, AppError> {
+        use crate::schema::time_entries::dsl::*;
+
+        self.get_card_by_id(_board_id, _card_id)?;
+
+        let running = time_entries
+            .filter(card_id.eq(_card_id))
+            .filter(user_id.eq(dto.user_id))
+            .filter(ended_at.is_null());
+
+        let stopped: Option<TimeEntry> = match dto.note {
+            Some(_note) => diesel::update(running)
+                .set((ended_at.eq(diesel::dsl::now), note.eq(_note)))
+                .get_result(&self.connection),
+            None => diesel::update(running)
+                .set(ended_at.eq(diesel::dsl::now))
+                .get_result(&self.connection),
+        }
+        .optional()
+        .map_err(|err| AppError::from_diesel_err(err, "while stopping timer"))?;
+
+        stopped.ok_or_else(|| {
+            AppError::new(
+                "the user has no running timer on this card",
+                ErrorType::NotFound,
+            )
+        })
+    }
+
+    /// Stop the running timers of cards going to the trash, their users can start new ones elsewhere
+    pub fn stop_timers(&self, card_ids: &[i64]) -> Result<usize, AppError> {
+        use crate::schema::time_entries::dsl::*;
+
+        diesel::update(
+            time_entries
+                .filter(card_id.eq_any(card_ids))
+                .filter(ended_at.is_null()),
+        )
+        .set(ended_at.eq(diesel::dsl::now))
+        .execute(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while stopping timers"))
+    }
+
+    /// Minutes tracked on each card, running timers counted up to now
+    /// Cards without time entries are left out
+    pub fn tracked_minutes(&self, card_ids: &[i64]) -> Result<HashMap<i64, i64>, AppError> {
+        use crate::schema::time_entries::dsl::*;
+
+        let entries: Vec<TimeEntry> = time_entries
+            .filter(card_id.eq_any(card_ids))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving time entries"))?;
+
+        let now = Utc::now();
+        let mut seconds: HashMap<i64, i64> = HashMap::new();
+        for entry in entries {
+            *seconds.entry(entry.card_id).or_default() += entry.seconds(now);
+        }
+
+        Ok(seconds
+            .into_iter()
+            .map(|(card, seconds)| (card, seconds / 60))
+            .collect())
+    }
+
+    /// Estimated against tracked time of the live cards planned in a range of days
+    /// Days are read in `time_zone`, as for the total duration of a day
+    pub fn effort_report(
+        &self,
+        _board_id: i64,
+        range: DateRangeQuery,
+        time_zone: Tz,
+    ) -> Result<EffortReport, AppError> {
+        use crate::schema::data;
+
+        self.get_board(_board_id)?;
+
+        let (start, _) = day_bounds(range.from, time_zone);
+        let (_, end) = day_bounds(range.to, time_zone);
+        let cards: Vec<Card> = data::table
+            .filter(data::board_id.eq(_board_id))
+            .filter(
+                data::due_at
+                    .is_null()
+                    .and(data::date.between(range.from, range.to))
+                    .or(data::due_at.ge(start).and(data::due_at.lt(end))),
+            )
+            .filter(data::deleted_at.is_null())
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving cards"))?;
+
+        let ids: Vec<i64> = cards.iter().map(|card| card.id).collect();
+        let tracked = self.tracked_minutes(&ids)?;
+
+        let mut efforts: Vec<CardEffort> = cards
+            .into_iter()
+            .map(|card| CardEffort {
+                card_id: card.id,
+                date: card.due_at.map_or(card.date, |due_at| {
+                    local_date(due_at, Some(time_zone.name()))
+                }),
+                estimated_minutes: i64::from(card.duration) * 60,
+                tracked_minutes: tracked.get(&card.id).copied().unwrap_or_default(),
+                title: card.title,
+            })
+            .collect();
+        efforts.sort_by_key(|effort| (effort.date, effort.card_id));
+
+        Ok(EffortReport {
+            from: range.from,
+            to: range.to,
+            time_zone: time_zone.name().to_string(),
+            estimated_minutes: efforts.iter().map(|effort| effort.estimated_minutes).sum(),
+            tracked_minutes: efforts.iter().map(|effort| effort.tracked_minutes).sum(),
+            cards: efforts,
+        })
+    }
+}
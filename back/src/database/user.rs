@@ -0,0 +1,201 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{AssigneeDuration, CardAssignee, CardView, PlannedDuration, User, UserData},
+    errors::{AppError, ErrorType},
+};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use diesel::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+
+/// Unique violations on `users` can only come from the user name
+const USER_NAME_TAKEN: &str = "a user with this name already exists";
+
+impl DBAccessManager {
+    pub fn create_user(&self, dto: UserData) -> Result<User, AppError> {
+        use crate::schema::users;
+
+        diesel::insert_into(users::table)
+            .values(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while creating user", USER_NAME_TAKEN)
+            })
+    }
+
+    pub fn get_user(&self, _id: i64) -> Result<User, AppError> {
+        use crate::schema::users::dsl::*;
+
+        users
+            .filter(id.eq(_id))
+            .first(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving user"))
+    }
+
+    pub fn get_all_users(&self) -> Result<Vec<User>, AppError> {
+        use crate::schema::users::dsl::*;
+
+        users
+            .order((name.asc(), id.asc()))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving all users"))
+    }
+
+    pub fn edit_user(&self, _id: i64, dto: UserData) -> Result<User, AppError> {
+        use crate::schema::users::dsl::*;
+
+        diesel::update(users.filter(id.eq(_id)))
+            .set(&dto)
+            .get_result(&self.connection)
+            .map_err(|err| {
+                AppError::from_unique_violation(err, "while updating user", USER_NAME_TAKEN)
+            })
+    }
+
+    /// Delete a user, it is unassigned from every card
+    pub fn delete_user(&self, _id: i64) -> Result<usize, AppError> {
+        use crate::schema::users::dsl::*;
+
+        let deleted = diesel::delete(users.filter(id.eq(_id)))
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while deleting user"))?;
+
+        if deleted == 0 {
+            return Err(AppError::new("user not found", ErrorType::NotFound));
+        }
+
+        Ok(deleted)
+    }
+
+    /// Assign a user to a card, assigning them twice is a no-op
+    /// # Returns
+    /// * `AppError` - Conflict when the user is at a WIP limit, or over their daily capacity
+    ///   under the reject policy unless the write allows overbooking
+    ///
+    pub fn assign_user(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _user_id: i64,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::card_assignees;
+
+        let card = self.get_card_by_id(_board_id, _card_id)?;
+        self.get_user(_user_id)?;
+
+        self.connection.transaction::<_, AppError, _>(|| {
+            let assigned: i64 = card_assignees::table
+                .filter(card_assignees::card_id.eq(card.id))
+                .filter(card_assignees::user_id.eq(_user_id))
+                .count()
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving card assignees"))?;
+            if assigned == 0 {
+                self.check_assignment_wip_limit(&card, _user_id)?;
+            }
+
+            diesel::insert_into(card_assignees::table)
+                .values(&CardAssignee {
+                    card_id: card.id,
+                    user_id: _user_id,
+                })
+                .on_conflict_do_nothing()
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while assigning user"))?;
+
+            // The card now counts in the day of the user
+            if assigned == 0 {
+                self.check_capacity(&card)?;
+            }
+            Ok(())
+        })?;
+
+        self.card_view(card)
+    }
+
+    /// Unassign a user from a card, unassigning someone who is not assigned is a no-op
+    pub fn unassign_user(
+        &self,
+        _board_id: i64,
+        _card_id: i64,
+        _user_id: i64,
+    ) -> Result<CardView, AppError> {
+        use crate::schema::card_assignees::dsl::*;
+
+        let card = self.get_card_by_id(_board_id, _card_id)?;
+
+        diesel::delete(
+            card_assignees
+                .filter(card_id.eq(card.id))
+                .filter(user_id.eq(_user_id)),
+        )
+        .execute(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while unassigning user"))?;
+
+        self.card_view(card)
+    }
+
+    /// Hours planned on a date on the live cards of a board, per assignee
+    /// # Arguments
+    /// * `_board_id` - Board of the cards
+    /// * `_date` - The day, cards due at a time are counted when it falls on that day in `time_zone`
+    /// * `time_zone` - Time zone the day is read in
+    ///
+    pub fn check_total_duration_on_date(
+        &self,
+        _board_id: i64,
+        _date: NaiveDate,
+        time_zone: Tz,
+    ) -> Result<PlannedDuration, AppError> {
+        use crate::schema::{card_assignees, data, users};
+
+        let cards: Vec<(i64, i32)> = data::table
+            .filter(data::board_id.eq(_board_id))
+            .filter(super::planned_on(_date, time_zone))
+            .filter(data::deleted_at.is_null())
+            .select((data::id, data::duration))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving total duration"))?;
+
+        let ids: Vec<i64> = cards.iter().map(|(id, _)| *id).collect();
+        let assignments: Vec<(i64, i64, String)> = card_assignees::table
+            .inner_join(users::table)
+            .filter(card_assignees::card_id.eq_any(&ids))
+            .select((card_assignees::card_id, users::id, users::name))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving assignees"))?;
+
+        let durations: BTreeMap<i64, i64> = cards
+            .iter()
+            .map(|(id, duration)| (*id, i64::from(*duration)))
+            .collect();
+
+        // Sorted by name, as the other user lists
+        let mut assignees: BTreeMap<(String, i64), i64> = BTreeMap::new();
+        let mut assigned: HashSet<i64> = HashSet::new();
+        for (card_id, user_id, name) in assignments {
+            *assignees.entry((name, user_id)).or_default() += durations[&card_id];
+            assigned.insert(card_id);
+        }
+        let unassigned = durations
+            .iter()
+            .filter(|(id, _)| !assigned.contains(id))
+            .map(|(_, duration)| duration)
+            .sum();
+
+        Ok(PlannedDuration {
+            date: _date,
+            time_zone: time_zone.name().to_string(),
+            total: durations.values().sum(),
+            unassigned,
+            assignees: assignees
+                .into_iter()
+                .map(|((name, user_id), duration)| AssigneeDuration {
+                    user_id,
+                    name,
+                    duration,
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,384 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{
+        Card, CardStatus, NewWorkflowColumn, Workflow, WorkflowColumn, WorkflowColumnData,
+        WorkflowData, WorkflowTransition,
+    },
+    errors::{AppError, ErrorType, FieldError},
+};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+impl DBAccessManager {
+    pub fn get_workflow(&self, _board_id: i64) -> Result<Workflow, AppError> {
+        use crate::schema::{workflow_columns, workflow_transitions};
+
+        self.get_board(_board_id)?;
+
+        let columns: Vec<WorkflowColumn> = workflow_columns::table
+            .filter(workflow_columns::board_id.eq(_board_id))
+            .order(workflow_columns::position.asc())
+            .select((
+                workflow_columns::status,
+                workflow_columns::name,
+                workflow_columns::color,
+                workflow_columns::position,
+                workflow_columns::wip_limit,
+                workflow_columns::assignee_wip_limit,
+            ))
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving workflow columns"))?;
+
+        let transitions: Vec<WorkflowTransition> = workflow_transitions::table
+            .filter(workflow_transitions::board_id.eq(_board_id))
+            .order((
+                workflow_transitions::from_status.asc(),
+                workflow_transitions::to_status.asc(),
+            ))
+            .load(&self.connection)
+            .map_err(|err| {
+                AppError::from_diesel_err(err, "while retrieving workflow transitions")
+            })?;
+
+        Ok(Workflow {
+            board_id: _board_id,
+            columns,
+            transitions,
+        })
+    }
+
+    /// Insert the columns of a board, positions follow the order of `columns`
+    pub fn insert_workflow_columns(
+        &self,
+        _board_id: i64,
+        columns: &[WorkflowColumnData],
+    ) -> Result<(), AppError> {
+        use crate::schema::workflow_columns;
+
+        let rows: Vec<NewWorkflowColumn> = columns
+            .iter()
+            .enumerate()
+            .map(|(position, column)| NewWorkflowColumn {
+                board_id: _board_id,
+                status: column.status,
+                name: &column.name,
+                color: &column.color,
+                position: position as i32,
+                wip_limit: column.wip_limit,
+                assignee_wip_limit: column.assignee_wip_limit,
+            })
+            .collect();
+
+        diesel::insert_into(workflow_columns::table)
+            .values(&rows)
+            .execute(&self.connection)
+            .map(|_| ())
+            .map_err(|err| AppError::from_diesel_err(err, "while creating workflow columns"))
+    }
+
+    /// Replace the columns and transitions of a board
+    /// # Returns
+    /// * `AppError` - Conflict when a removed column still holds cards
+    ///
+    pub fn replace_workflow(
+        &self,
+        _board_id: i64,
+        dto: WorkflowData,
+    ) -> Result<Workflow, AppError> {
+        use crate::schema::{boards, data, workflow_columns, workflow_transitions};
+
+        self.connection.transaction(|| {
+            // Serializes workflow changes with the card writes checking it
+            boards::table
+                .filter(boards::id.eq(_board_id))
+                .select(boards::id)
+                .for_update()
+                .first::<i64>(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while retrieving board"))?;
+
+            let kept: Vec<CardStatus> = dto.columns.iter().map(|column| column.status).collect();
+            let orphans: i64 = data::table
+                .filter(data::board_id.eq(_board_id))
+                .filter(data::status.ne_all(kept))
+                .count()
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while counting cards"))?;
+
+            if orphans > 0 {
+                return Err(AppError::new(
+                    format!(
+                        "{} card(s) are in a status without a column, move them first",
+                        orphans
+                    )
+                    .as_str(),
+                    ErrorType::Conflict,
+                ));
+            }
+
+            diesel::delete(
+                workflow_transitions::table.filter(workflow_transitions::board_id.eq(_board_id)),
+            )
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while replacing workflow"))?;
+
+            diesel::delete(
+                workflow_columns::table.filter(workflow_columns::board_id.eq(_board_id)),
+            )
+            .execute(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while replacing workflow"))?;
+
+            self.insert_workflow_columns(_board_id, &dto.columns)?;
+
+            let transitions: Vec<WorkflowTransition> = dto
+                .transitions
+                .into_iter()
+                .map(|transition| WorkflowTransition {
+                    board_id: _board_id,
+                    ..transition
+                })
+                .collect();
+
+            diesel::insert_into(workflow_transitions::table)
+                .values(&transitions)
+                .on_conflict_do_nothing()
+                .execute(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while replacing workflow"))?;
+
+            self.get_workflow(_board_id)
+        })
+    }
+
+    /// Check a card of a board can be put in a status
+    /// The board stays share-locked until the end of the card write, a replaced workflow waits for it
+    /// # Arguments
+    /// * `_board_id` - Board of the card
+    /// * `from` - Current status of the card, `None` when it is being created
+    /// * `to` - Requested status
+    /// # Returns
+    /// * `AppError` - BadRequest when the board has no such column or forbids the move
+    ///
+    pub fn check_status_change(
+        &self,
+        _board_id: i64,
+        from: Option<CardStatus>,
+        to: CardStatus,
+    ) -> Result<(), AppError> {
+        use crate::schema::boards;
+
+        boards::table
+            .filter(boards::id.eq(_board_id))
+            .select(boards::id)
+            .for_share()
+            .first::<i64>(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while retrieving board"))?;
+        let workflow = self.get_workflow(_board_id)?;
+
+        if !workflow.has_column(to) {
+            return Err(AppError::new(
+                format!("this board has no `{}` column", to).as_str(),
+                ErrorType::BadRequest,
+            ));
+        }
+
+        match from {
+            Some(from) if !workflow.allows(from, to) => {
+                let allowed: Vec<&str> = workflow
+                    .transitions
+                    .iter()
+                    .filter(|transition| transition.from_status == from)
+                    .map(|transition| transition.to_status.as_str())
+                    .collect();
+
+                Err(AppError::new(
+                    format!(
+                        "illegal transition from `{}` to `{}`, allowed: [{}]",
+                        from,
+                        to,
+                        allowed.join(", ")
+                    )
+                    .as_str(),
+                    ErrorType::BadRequest,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Lock the column of a status until the end of the transaction and read its WIP limits,
+    /// two cards entering the column at once could otherwise both take its last place
+    /// # Returns
+    /// * `(Option<i32>, Option<i32>)` - Limits of the column and of each assignee in it
+    ///
+    fn lock_wip_limits(
+        &self,
+        _board_id: i64,
+        _status: CardStatus,
+    ) -> Result<(Option<i32>, Option<i32>), AppError> {
+        use crate::schema::workflow_columns::dsl::*;
+
+        workflow_columns
+            .filter(board_id.eq(_board_id))
+            .filter(status.eq(_status))
+            .select((wip_limit, assignee_wip_limit))
+            .for_update()
+            .first(&self.connection)
+            .optional()
+            .map(Option::unwrap_or_default)
+            .map_err(|err| AppError::from_diesel_err(err, "while locking workflow column"))
+    }
+
+    /// Refuse a write going over a WIP limit, unless the limits are overridden
+    /// The refusal carries the current `count` and the `limit` as fields, for clients that act on them
+    fn wip_limit_reached(&self, message: String, count: i64, limit: i32) -> Result<(), AppError> {
+        if self.wip_override {
+            log::warn!(
+                "WIP limit overridden by {}: {}",
+                self.author.as_deref().unwrap_or("an anonymous client"),
+                message
+            );
+            return Ok(());
+        }
+
+        Err(AppError::with_fields(
+            message.as_str(),
+            ErrorType::Conflict,
+            vec![
+                FieldError::new("count", &count.to_string()),
+                FieldError::new("limit", &limit.to_string()),
+            ],
+        ))
+    }
+
+    /// Check the users hold fewer live cards of a column than its limit per assignee,
+    /// the card itself left out
+    fn check_assignee_wip_limit(
+        &self,
+        _board_id: i64,
+        card_id: i64,
+        _status: CardStatus,
+        limit: i32,
+        user_ids: &[i64],
+    ) -> Result<(), AppError> {
+        use crate::schema::{card_assignees, data};
+
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        let assigned: Vec<i64> = card_assignees::table
+            .inner_join(data::table)
+            .filter(data::board_id.eq(_board_id))
+            .filter(data::status.eq(_status))
+            .filter(data::deleted_at.is_null())
+            .filter(data::id.ne(card_id))
+            .filter(card_assignees::user_id.eq_any(user_ids))
+            .select(card_assignees::user_id)
+            .load(&self.connection)
+            .map_err(|err| AppError::from_diesel_err(err, "while counting assigned cards"))?;
+
+        let mut counts: HashMap<i64, i64> = HashMap::new();
+        for user_id in assigned {
+            *counts.entry(user_id).or_default() += 1;
+        }
+
+        match user_ids
+            .iter()
+            .find(|user_id| counts.get(user_id).copied().unwrap_or_default() >= i64::from(limit))
+        {
+            Some(user_id) => self.wip_limit_reached(
+                format!(
+                    "user {} is at the WIP limit of `{}`: {} card(s) for a limit of {}",
+                    user_id, _status, counts[user_id], limit
+                ),
+                counts[user_id],
+                limit,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Check a card can enter a column without going over its WIP limits
+    /// Cards staying in their column are not checked, limits lowered below the current count
+    /// only hold new cards back
+    /// # Arguments
+    /// * `_board_id` - Board of the card
+    /// * `card` - The card, `None` when it is being created
+    /// * `to` - Requested status
+    /// # Returns
+    /// * `AppError` - Conflict when the column, or one of the card assignees in it, is full,
+    ///   with its `count` and `limit` as fields
+    ///
+    pub fn check_wip_limit(
+        &self,
+        _board_id: i64,
+        card: Option<&Card>,
+        to: CardStatus,
+    ) -> Result<(), AppError> {
+        use crate::schema::{card_assignees, data};
+
+        if card.is_some_and(|card| card.status == to && card.deleted_at.is_none()) {
+            return Ok(());
+        }
+
+        let (limit, assignee_limit) = self.lock_wip_limits(_board_id, to)?;
+
+        if let Some(limit) = limit {
+            let mut statement = data::table
+                .filter(data::board_id.eq(_board_id))
+                .filter(data::status.eq(to))
+                .filter(data::deleted_at.is_null())
+                .into_boxed();
+            if let Some(card) = card {
+                statement = statement.filter(data::id.ne(card.id));
+            }
+            let count: i64 = statement
+                .count()
+                .get_result(&self.connection)
+                .map_err(|err| AppError::from_diesel_err(err, "while counting cards"))?;
+
+            if count >= i64::from(limit) {
+                self.wip_limit_reached(
+                    format!(
+                        "column `{}` is at its WIP limit: {} card(s) for a limit of {}",
+                        to, count, limit
+                    ),
+                    count,
+                    limit,
+                )?;
+            }
+        }
+
+        match (assignee_limit, card) {
+            (Some(assignee_limit), Some(card)) => {
+                let user_ids: Vec<i64> = card_assignees::table
+                    .filter(card_assignees::card_id.eq(card.id))
+                    .select(card_assignees::user_id)
+                    .load(&self.connection)
+                    .map_err(|err| {
+                        AppError::from_diesel_err(err, "while retrieving card assignees")
+                    })?;
+
+                self.check_assignee_wip_limit(_board_id, card.id, to, assignee_limit, &user_ids)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a user can be assigned to a card without going over the limit per assignee
+    /// of its column
+    /// # Returns
+    /// * `AppError` - Conflict when the user already holds as many cards of the column as allowed
+    ///
+    pub fn check_assignment_wip_limit(&self, card: &Card, _user_id: i64) -> Result<(), AppError> {
+        match self.lock_wip_limits(card.board_id, card.status)? {
+            (_, Some(assignee_limit)) => self.check_assignee_wip_limit(
+                card.board_id,
+                card.id,
+                card.status,
+                assignee_limit,
+                &[_user_id],
+            ),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::DBAccessManager;
+use crate::{
+    domain::{Workload, WorkloadGroup, WorkloadPeriod, WorkloadQuery},
+    errors::AppError,
+};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use diesel::{
+    prelude::*,
+    sql_types::{BigInt, Date, Nullable, Text},
+};
+use std::collections::BTreeMap;
+
+/// Hours planned in a period for one value of the split field
+#[derive(QueryableByName)]
+struct WorkloadRow {
+    #[sql_type = "Date"]
+    start: NaiveDate,
+    #[sql_type = "Nullable<Text>"]
+    key: Option<String>,
+    #[sql_type = "BigInt"]
+    duration: i64,
+    #[sql_type = "BigInt"]
+    cards: i64,
+}
+
+impl DBAccessManager {
+    /// Hours planned on the live cards of a range of days, by period and optionally by status or priority
+    /// Every period and every value of the split field is listed, with zero hours when nothing is planned
+    /// Cards with a due time count on the day it falls on in `time_zone`, as for the check route
+    pub fn get_workload(
+        &self,
+        _board_id: i64,
+        query: WorkloadQuery,
+        time_zone: Tz,
+    ) -> Result<Workload, AppError> {
+        self.get_board(_board_id)?;
+
+        let period = query.period();
+        let split = query.split();
+        // Built from the grouping only, never from the request text
+        let (keys, key_match) = match split {
+            Some(WorkloadGroup::Status) => (
+                "SELECT unnest(enum_range(NULL::card_status))::text",
+                "data.status::text = keys.key",
+            ),
+            Some(WorkloadGroup::Priority) => (
+                "SELECT unnest(enum_range(NULL::card_priority))::text",
+                "data.priority::text = keys.key",
+            ),
+            _ => ("SELECT NULL::text", "TRUE"),
+        };
+
+        let rows: Vec<WorkloadRow> = diesel::sql_query(format!(
+            "SELECT periods.start::date AS start, keys.key AS key, \
+                COALESCE(SUM(data.duration), 0) AS duration, COUNT(data.id) AS cards \
+             FROM generate_series(date_trunc($1, $2::timestamp), $3::timestamp, ('1 ' || $1)::interval) \
+                AS periods (start) \
+             CROSS JOIN ({}) AS keys (key) \
+             LEFT JOIN data ON data.board_id = $4 AND data.deleted_at IS NULL \
+                AND COALESCE((data.due_at AT TIME ZONE $5)::date, data.date) BETWEEN $2 AND $3 \
+                AND date_trunc($1, COALESCE((data.due_at AT TIME ZONE $5)::date, data.date)::timestamp) \
+                    = periods.start AND {} \
+             GROUP BY periods.start, keys.key \
+             ORDER BY periods.start, keys.key",
+            keys, key_match
+        ))
+        .bind::<Text, _>(period.as_str())
+        .bind::<Date, _>(query.from)
+        .bind::<Date, _>(query.to)
+        .bind::<BigInt, _>(_board_id)
+        .bind::<Text, _>(time_zone.name())
+        .load(&self.connection)
+        .map_err(|err| AppError::from_diesel_err(err, "while retrieving workload"))?;
+
+        let mut periods: Vec<WorkloadPeriod> = Vec::new();
+        for row in rows {
+            let current = match periods.last_mut() {
+                Some(current) if current.start == row.start => current,
+                _ => {
+                    periods.push(WorkloadPeriod {
+                        start: row.start,
+                        duration: 0,
+                        cards: 0,
+                        split: split.map(|_| BTreeMap::new()),
+                    });
+                    periods.last_mut().unwrap()
+                }
+            };
+            current.duration += row.duration;
+            current.cards += row.cards;
+            if let (Some(split), Some(key)) = (current.split.as_mut(), row.key) {
+                split.insert(key, row.duration);
+            }
+        }
+
+        Ok(Workload {
+            from: query.from,
+            to: query.to,
+            time_zone: time_zone.name().to_string(),
+            period: period.as_str(),
+            split: split.map(|split| split.as_str()),
+            duration: periods.iter().map(|period| period.duration).sum(),
+            periods,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{card::respond_card, database::DBAccessManager, domain::CardView};
+
+/// Make a card wait for another one of the board.
+/// Swagger config:
+///   - Operation ID: add_blocker
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/blocked_by/{blocker_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: blocker_id (integer, card of the same board)
+///   - Response: JSON representation of the card with its blockers, with its ETag
+///   - Error 400: a card cannot block itself
+///   - Error 409: the link would form a cycle
+pub async fn add_blocker(
+    _board_id: i64,
+    _id: i64,
+    _blocker_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.add_blocker(_board_id, _id, _blocker_id) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
+
+/// Stop a card from waiting for another one.
+/// Swagger config:
+///   - Operation ID: remove_blocker
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/blocked_by/{blocker_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: blocker_id (integer)
+///   - Response: JSON representation of the card with its blockers, with its ETag
+pub async fn remove_blocker(
+    _board_id: i64,
+    _id: i64,
+    _blocker_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.remove_blocker(_board_id, _id, _blocker_id) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
@@ -1,6 +1,289 @@
-use super::schema::data;
-use chrono::NaiveDate;
-use serde::{Deserialize, Serialize};
+use super::schema::{
+    attachments, boards, card_assignees, card_dependencies, card_labels, card_revisions,
+    card_templates, checklist_items, comments, custom_fields, data, labels, users,
+    workflow_columns, workflow_transitions,
+};
+use crate::errors::{AppError, ErrorType};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::Pg,
+    serialize::{self, IsNull, Output, ToSql},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{collections::BTreeMap, fmt, io::Write, str::FromStr};
+
+/// Board used by the card routes that are not nested under `/board/{id}`
+pub const DEFAULT_BOARD_ID: i64 = 1;
+
+/// Postgres enum types used by the `data`, `custom_fields` and `boards` tables
+pub mod sql_types {
+    #![allow(non_camel_case_types)]
+
+    #[derive(SqlType, QueryId)]
+    #[postgres(type_name = "card_status")]
+    pub struct Card_status;
+
+    #[derive(SqlType, QueryId)]
+    #[postgres(type_name = "card_priority")]
+    pub struct Card_priority;
+
+    #[derive(SqlType, QueryId)]
+    #[postgres(type_name = "custom_field_type")]
+    pub struct Custom_field_type;
+
+    #[derive(SqlType, QueryId)]
+    #[postgres(type_name = "capacity_policy")]
+    pub struct Capacity_policy;
+}
+
+/// Board column a card is displayed in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[serde(rename_all = "lowercase")]
+#[sql_type = "sql_types::Card_status"]
+pub enum CardStatus {
+    Todo,
+    InProgress,
+    Completed,
+}
+
+impl CardStatus {
+    /// Value used both on the wire and in the `card_status` Postgres enum
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardStatus::Todo => "todo",
+            CardStatus::InProgress => "inprogress",
+            CardStatus::Completed => "completed",
+        }
+    }
+}
+
+/// Priority of a card
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[serde(rename_all = "lowercase")]
+#[sql_type = "sql_types::Card_priority"]
+pub enum CardPriority {
+    Low,
+    Medium,
+    High,
+}
+
+impl CardPriority {
+    /// Value used both on the wire and in the `card_priority` Postgres enum
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardPriority::Low => "low",
+            CardPriority::Medium => "medium",
+            CardPriority::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for CardStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for CardPriority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for CardStatus {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "todo" => Ok(CardStatus::Todo),
+            "inprogress" => Ok(CardStatus::InProgress),
+            "completed" => Ok(CardStatus::Completed),
+            _ => Err(AppError::new(
+                format!(
+                    "FIELD_ERROR: unknown status `{}`, expected one of `todo`, `inprogress`, `completed`",
+                    value
+                )
+                .as_str(),
+                ErrorType::BadRequest,
+            )),
+        }
+    }
+}
+
+impl FromStr for CardPriority {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "low" => Ok(CardPriority::Low),
+            "medium" => Ok(CardPriority::Medium),
+            "high" => Ok(CardPriority::High),
+            _ => Err(AppError::new(
+                format!(
+                    "FIELD_ERROR: unknown priority `{}`, expected one of `low`, `medium`, `high`",
+                    value
+                )
+                .as_str(),
+                ErrorType::BadRequest,
+            )),
+        }
+    }
+}
+
+impl ToSql<sql_types::Card_status, Pg> for CardStatus {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::Card_status, Pg> for CardStatus {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let value = std::str::from_utf8(not_none!(bytes))?;
+        value.parse().map_err(|err: AppError| err.message.into())
+    }
+}
+
+impl ToSql<sql_types::Card_priority, Pg> for CardPriority {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::Card_priority, Pg> for CardPriority {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let value = std::str::from_utf8(not_none!(bytes))?;
+        value.parse().map_err(|err: AppError| err.message.into())
+    }
+}
+
+/// Type of the values of a custom field
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[serde(rename_all = "lowercase")]
+#[sql_type = "sql_types::Custom_field_type"]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    /// Day, as YYYY-MM-DD
+    Date,
+    /// One of the options of the field
+    Select,
+    /// Absolute http or https URL
+    Url,
+}
+
+impl CustomFieldType {
+    /// Value used both on the wire and in the `custom_field_type` Postgres enum
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Select => "select",
+            CustomFieldType::Url => "url",
+        }
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(CustomFieldType::Text),
+            "number" => Ok(CustomFieldType::Number),
+            "date" => Ok(CustomFieldType::Date),
+            "select" => Ok(CustomFieldType::Select),
+            "url" => Ok(CustomFieldType::Url),
+            _ => Err(AppError::new(
+                format!(
+                    "FIELD_ERROR: unknown custom field type `{}`, expected one of `text`, `number`, `date`, `select`, `url`",
+                    value
+                )
+                .as_str(),
+                ErrorType::BadRequest,
+            )),
+        }
+    }
+}
+
+impl ToSql<sql_types::Custom_field_type, Pg> for CustomFieldType {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::Custom_field_type, Pg> for CustomFieldType {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let value = std::str::from_utf8(not_none!(bytes))?;
+        value.parse().map_err(|err: AppError| err.message.into())
+    }
+}
+
+/// What a card write going over a daily capacity does
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[serde(rename_all = "lowercase")]
+#[sql_type = "sql_types::Capacity_policy"]
+pub enum CapacityPolicy {
+    /// The write goes through with an `X-Capacity-Warning` header
+    Warn,
+    /// The write is refused, unless it allows overbooking
+    Reject,
+}
+
+impl CapacityPolicy {
+    /// Value used both on the wire and in the `capacity_policy` Postgres enum
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapacityPolicy::Warn => "warn",
+            CapacityPolicy::Reject => "reject",
+        }
+    }
+}
+
+impl FromStr for CapacityPolicy {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "warn" => Ok(CapacityPolicy::Warn),
+            "reject" => Ok(CapacityPolicy::Reject),
+            _ => Err(AppError::new(
+                format!(
+                    "FIELD_ERROR: unknown capacity policy `{}`, expected `warn` or `reject`",
+                    value
+                )
+                .as_str(),
+                ErrorType::BadRequest,
+            )),
+        }
+    }
+}
+
+impl ToSql<sql_types::Capacity_policy, Pg> for CapacityPolicy {
+    fn to_sql<W: Write>(&self, out: &mut Output<W, Pg>) -> serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::Capacity_policy, Pg> for CapacityPolicy {
+    fn from_sql(bytes: Option<&[u8]>) -> deserialize::Result<Self> {
+        let value = std::str::from_utf8(not_none!(bytes))?;
+        value.parse().map_err(|err: AppError| err.message.into())
+    }
+}
+
+/// Hours that can be planned on a day, used when a board or a user sets none
+#[derive(Debug, Clone, Copy)]
+pub struct DailyCapacity {
+    pub hours: i32,
+    pub policy: CapacityPolicy,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, Queryable)]
 pub struct Card {
@@ -8,18 +291,1147 @@ pub struct Card {
     pub title: String,
     pub description: String,
     pub date: NaiveDate,
-    pub priority: String,
+    pub priority: CardPriority,
     pub duration: i32,
-    pub status: String,
+    pub status: CardStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub board_id: i64,
+    /// Position of the card in its column, only meaningful compared to the other ranks
+    pub rank: String,
+    /// Recurrence the card was generated by
+    pub recurrence_id: Option<i64>,
+    /// Deadline of the card, `date` is its day in `time_zone`
+    pub due_at: Option<DateTime<Utc>>,
+    /// IANA name of the time zone the card is planned in, such as `Europe/Paris`
+    pub time_zone: Option<String>,
+    /// Card this one breaks down, such as its epic
+    pub parent_id: Option<i64>,
+    /// Values of the custom fields of the board, keyed by field name
+    #[serde(default)]
+    pub custom: serde_json::Value,
+}
+
+/// Resources exposing their version as an `ETag`
+pub trait Versioned {
+    /// Strong entity tag derived from the version
+    fn etag(&self) -> String;
+}
+
+impl Versioned for Card {
+    fn etag(&self) -> String {
+        format!("\"{}\"", self.version)
+    }
+}
+
+/// Card as returned by the read routes, with the rows attached to it
+#[derive(Serialize, Debug, Clone)]
+pub struct CardView {
+    #[serde(flatten)]
+    pub card: Card,
+    pub labels: Vec<Label>,
+    pub assignees: Vec<User>,
+    /// Ids of the live cards this one waits for
+    pub blocked_by: Vec<i64>,
+    /// Whether one of them is not completed yet
+    pub blocked: bool,
+    /// Time tracked on the card, running timers included
+    pub tracked_minutes: i64,
+    /// Progress of the live children, for cards that have some
+    pub rollup: Option<ChildRollup>,
+    pub checklist: ChecklistProgress,
+    pub comment_count: i64,
+}
+
+impl Versioned for CardView {
+    fn etag(&self) -> String {
+        self.card.etag()
+    }
+}
+
+/// Card as returned by the write routes, with the daily capacities its day goes over
+#[derive(Serialize, Debug, Clone)]
+pub struct CapacityChecked<T> {
+    #[serde(flatten)]
+    pub card: T,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capacity_warnings: Vec<String>,
+}
+
+impl<T: Versioned> Versioned for CapacityChecked<T> {
+    fn etag(&self) -> String {
+        self.card.etag()
+    }
 }
 
 #[derive(Debug, Clone, Insertable, Deserialize)]
 #[table_name = "data"]
 pub struct CardData {
+    pub title: String,
+    pub description: String,
+    /// Ignored when `due_at` is set, the card is planned on the day of its due time
+    pub date: NaiveDate,
+    pub priority: CardPriority,
+    pub duration: i32,
+    pub status: CardStatus,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// Values of the custom fields, the stored ones are kept when absent from an edit
+    #[serde(default)]
+    pub custom: Option<serde_json::Value>,
+}
+
+impl CardData {
+    /// Plan the card on the day of its due time, if it has one
+    pub fn align_date(mut self) -> CardData {
+        if let Some(due_at) = self.due_at {
+            self.date = local_date(due_at, self.time_zone.as_deref());
+        }
+        self
+    }
+}
+
+/// Blueprint cards of a board are created from
+/// The strings of `card` and `checklist` may hold `{{today}}` or `{{date+N}}` placeholders
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct CardTemplate {
+    pub id: i64,
+    pub board_id: i64,
+    pub name: String,
+    /// Shaped like a CardData once its placeholders are resolved
+    pub card: serde_json::Value,
+    pub checklist: Vec<String>,
+    pub label_ids: Vec<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable, AsChangeset, Deserialize)]
+#[table_name = "card_templates"]
+pub struct CardTemplateData {
+    pub name: String,
+    pub card: serde_json::Value,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+    #[serde(default)]
+    pub label_ids: Vec<i64>,
+}
+
+/// Card and checklist of a template, its placeholders resolved against `today`
+pub fn instantiate_template(
+    card: &serde_json::Value,
+    checklist: &[String],
+    today: NaiveDate,
+) -> Result<(CardData, Vec<String>), String> {
+    let card = crate::placeholder::resolve(card, today)?;
+    let card: CardData = serde_json::from_value(card).map_err(|err| err.to_string())?;
+    let checklist = checklist
+        .iter()
+        .map(|text| crate::placeholder::resolve_str(text, today))
+        .collect::<Result<_, _>>()?;
+
+    Ok((card, checklist))
+}
+
+/// Time zone a card is read in, UTC when the zone is missing or unknown
+pub fn zone_or_utc(time_zone: Option<&str>) -> Tz {
+    time_zone
+        .and_then(|zone| zone.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Day of an instant in a time zone, UTC when the zone is missing or unknown
+pub fn local_date(instant: DateTime<Utc>, time_zone: Option<&str>) -> NaiveDate {
+    instant.with_timezone(&zone_or_utc(time_zone)).date_naive()
+}
+
+/// Instants a day spans in a time zone, from its first instant included to the next day's excluded
+pub fn day_bounds(day: NaiveDate, time_zone: Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start_of = |day: NaiveDate| {
+        let midnight = day.and_hms_opt(0, 0, 0).unwrap_or_default();
+
+        // Zones skipping midnight on a DST change start the day at the end of the gap
+        time_zone
+            .from_local_datetime(&midnight)
+            .earliest()
+            .or_else(|| {
+                time_zone
+                    .from_local_datetime(&(midnight + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map_or_else(
+                || Utc.from_utc_datetime(&midnight),
+                |start| start.with_timezone(&Utc),
+            )
+    };
+
+    let end = day
+        .checked_add_signed(chrono::Duration::days(1))
+        .map_or(DateTime::<Utc>::MAX_UTC, start_of);
+
+    (start_of(day), end)
+}
+
+/// Partial update of a card, following JSON Merge Patch semantics:
+/// absent fields are left untouched, `null` clears the nullable columns and is refused for the others
+#[derive(Debug, Clone, Default, AsChangeset, Deserialize)]
+#[table_name = "data"]
+pub struct CardPatch {
+    #[serde(default, deserialize_with = "non_null")]
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub priority: Option<CardPriority>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub duration: Option<i32>,
+    #[serde(default, deserialize_with = "non_null")]
+    pub status: Option<CardStatus>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub due_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub time_zone: Option<Option<String>>,
+    /// Merged into the stored values, `null` clears a field
+    #[serde(default, deserialize_with = "non_null")]
+    pub custom: Option<serde_json::Value>,
+}
+
+impl CardPatch {
+    /// True when the patch does not touch any column
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.date.is_none()
+            && self.priority.is_none()
+            && self.duration.is_none()
+            && self.status.is_none()
+            && self.due_at.is_none()
+            && self.time_zone.is_none()
+            && self.custom.is_none()
+    }
+}
+
+/// Deserialize a present field, refusing an explicit `null`
+fn non_null<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Deserialize a present field, an explicit `null` being kept apart from an absent field
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Column the card list can be sorted on
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CardSortKey {
+    /// Column then position in the column, as arranged on the board
+    Rank,
+    Id,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Direction of the card list sort
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// How the labels of the `label` filter are combined
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelMatch {
+    /// Cards carrying at least one of the labels
+    Any,
+    /// Cards carrying every label
+    All,
+}
+
+/// Query parameters accepted by the card list
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CardListQuery {
+    /// Only return cards modified at or after this instant (RFC 3339)
+    pub updated_since: Option<DateTime<Utc>>,
+    pub sort: Option<CardSortKey>,
+    pub order: Option<SortOrder>,
+    /// Comma separated label ids, such as `label=1,4`
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub label: Vec<i64>,
+    pub label_match: Option<LabelMatch>,
+    /// Comma separated user ids, cards assigned to any of them, such as `assignee=2,3`
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub assignee: Vec<i64>,
+    /// Comma separated custom field values the cards must all have, such as `custom=customer:ACME`
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub custom: Vec<CustomFieldFilter>,
+}
+
+/// Value a custom field of the listed cards must have, as `name:value`
+#[derive(Debug, Clone)]
+pub struct CustomFieldFilter {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for CustomFieldFilter {
+    type Err = String;
+
+    fn from_str(filter: &str) -> Result<Self, Self::Err> {
+        match filter.split_once(':') {
+            Some((name, value)) => Ok(CustomFieldFilter {
+                name: name.trim().to_string(),
+                value: value.trim().to_string(),
+            }),
+            None => Err(format!(
+                "custom filter `{}` is not a name:value pair",
+                filter
+            )),
+        }
+    }
+}
+
+/// Deserialize a comma separated list of values
+fn comma_separated<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    String::deserialize(deserializer)?
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Where to put a card, `before` and `after` are the ids of its new neighbours
+/// in the target column, the card goes to the end of the column without any
+#[derive(Deserialize, Debug, Clone)]
+pub struct CardMove {
+    pub status: CardStatus,
+    #[serde(default)]
+    pub before: Option<i64>,
+    #[serde(default)]
+    pub after: Option<i64>,
+}
+
+/// Operation that produced a card revision
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RevisionAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Revert,
+}
+
+impl RevisionAction {
+    /// Value stored in `card_revisions.action`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionAction::Create => "create",
+            RevisionAction::Update => "update",
+            RevisionAction::Delete => "delete",
+            RevisionAction::Restore => "restore",
+            RevisionAction::Revert => "revert",
+        }
+    }
+}
+
+/// Snapshot of a card taken after each write
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct CardRevision {
+    pub id: i64,
+    pub card_id: i64,
+    pub version: i32,
+    pub action: String,
+    pub author: Option<String>,
+    pub snapshot: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields of a revision snapshot a revert writes back
+/// Snapshots recorded by earlier versions lack the fields added since, such as the rank
+#[derive(Deserialize, Debug, Clone)]
+pub struct RevisionSnapshot {
     pub title: String,
     pub description: String,
     pub date: NaiveDate,
-    pub priority: String,
+    pub priority: CardPriority,
     pub duration: i32,
-    pub status: String,
+    pub status: CardStatus,
+    pub due_at: Option<DateTime<Utc>>,
+    pub time_zone: Option<String>,
+    #[serde(default)]
+    pub custom: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "card_revisions"]
+pub struct NewCardRevision<'a> {
+    pub card_id: i64,
+    pub version: i32,
+    pub action: &'a str,
+    pub author: Option<&'a str>,
+    pub snapshot: serde_json::Value,
+}
+
+/// Revision as returned by the history route, with the fields it changed
+#[derive(Serialize, Debug, Clone)]
+pub struct CardHistoryEntry {
+    #[serde(flatten)]
+    pub revision: CardRevision,
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable)]
+pub struct Board {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Hours the cards of a day may add up to, the unassigned ones held against the server default when `None`
+    pub daily_capacity: Option<i32>,
+    /// What a write going over the capacity does, the server default when `None`
+    pub capacity_policy: Option<CapacityPolicy>,
+}
+
+#[derive(Debug, Clone, Insertable, AsChangeset, Deserialize)]
+#[table_name = "boards"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct BoardData {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub daily_capacity: Option<i32>,
+    #[serde(default)]
+    pub capacity_policy: Option<CapacityPolicy>,
+}
+
+/// Label of a board, attached to any number of its cards
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct Label {
+    pub id: i64,
+    pub board_id: i64,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable, AsChangeset, Deserialize)]
+#[table_name = "labels"]
+pub struct LabelData {
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "card_labels"]
+pub struct CardLabel {
+    pub card_id: i64,
+    pub label_id: i64,
+}
+
+/// Person cards can be assigned to
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Hours the cards assigned to the user may add up to on a day, the server default when `None`
+    pub daily_capacity: Option<i32>,
+}
+
+#[derive(Debug, Clone, Insertable, AsChangeset, Deserialize)]
+#[table_name = "users"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct UserData {
+    pub name: String,
+    #[serde(default)]
+    pub daily_capacity: Option<i32>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "card_assignees"]
+pub struct CardAssignee {
+    pub card_id: i64,
+    pub user_id: i64,
+}
+
+/// Link making `blocked_id` wait for `blocker_id` to be completed
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "card_dependencies"]
+pub struct CardDependency {
+    pub blocker_id: i64,
+    pub blocked_id: i64,
+}
+
+/// Repetition of a card, its occurrences are generated as new cards on the following dates
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct Recurrence {
+    pub id: i64,
+    /// Template card, its date is the first occurrence
+    pub card_id: i64,
+    /// RFC 5545 `RRULE`, see `RecurrenceRule` for the supported subset
+    pub rule: String,
+    /// Last date up to which occurrences have been generated
+    pub generated_until: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set when the card stopped repeating, the row is kept so no date is generated twice
+    #[serde(skip)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecurrenceData {
+    pub rule: String,
+}
+
+/// Time a user spent on a card, the timer is running while `ended_at` is empty
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct TimeEntry {
+    pub id: i64,
+    pub card_id: i64,
+    pub user_id: i64,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TimeEntry {
+    /// Seconds tracked, up to `now` for a running timer
+    pub fn seconds(&self, now: DateTime<Utc>) -> i64 {
+        (self.ended_at.unwrap_or(now) - self.started_at)
+            .num_seconds()
+            .max(0)
+    }
+}
+
+/// User starting or stopping a timer, the note is kept on the time entry
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimerData {
+    pub user_id: i64,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Range of days, both included
+#[derive(Deserialize, Debug, Clone)]
+pub struct DateRangeQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Estimated and tracked time of a card
+#[derive(Serialize, Debug, Clone)]
+pub struct CardEffort {
+    pub card_id: i64,
+    pub title: String,
+    pub date: NaiveDate,
+    /// The card duration, in minutes
+    pub estimated_minutes: i64,
+    pub tracked_minutes: i64,
+}
+
+/// Estimated against tracked time of the cards planned in a range of days
+#[derive(Serialize, Debug, Clone)]
+pub struct EffortReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Time zone the days were read in
+    pub time_zone: String,
+    pub estimated_minutes: i64,
+    pub tracked_minutes: i64,
+    pub cards: Vec<CardEffort>,
+}
+
+/// Part of the grouping of a workload: the length of its periods or a card field splitting them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkloadGroup {
+    Day,
+    /// Weeks start on Monday
+    Week,
+    Month,
+    Status,
+    Priority,
+}
+
+impl WorkloadGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkloadGroup::Day => "day",
+            WorkloadGroup::Week => "week",
+            WorkloadGroup::Month => "month",
+            WorkloadGroup::Status => "status",
+            WorkloadGroup::Priority => "priority",
+        }
+    }
+
+    /// Whether the group sets the length of the periods rather than splitting them
+    pub fn is_period(&self) -> bool {
+        matches!(
+            self,
+            WorkloadGroup::Day | WorkloadGroup::Week | WorkloadGroup::Month
+        )
+    }
+}
+
+impl FromStr for WorkloadGroup {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "day" => Ok(WorkloadGroup::Day),
+            "week" => Ok(WorkloadGroup::Week),
+            "month" => Ok(WorkloadGroup::Month),
+            "status" => Ok(WorkloadGroup::Status),
+            "priority" => Ok(WorkloadGroup::Priority),
+            _ => Err(format!(
+                "unknown workload group `{}`, expected day, week or month, then status or priority",
+                value
+            )),
+        }
+    }
+}
+
+/// Range of days of a workload and how its hours are grouped, such as `group_by=week,status`
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorkloadQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default, deserialize_with = "comma_separated")]
+    pub group_by: Vec<WorkloadGroup>,
+}
+
+impl WorkloadQuery {
+    /// Length of the periods, days when the grouping sets none
+    pub fn period(&self) -> WorkloadGroup {
+        self.group_by
+            .iter()
+            .copied()
+            .find(WorkloadGroup::is_period)
+            .unwrap_or(WorkloadGroup::Day)
+    }
+
+    /// Card field splitting the hours of each period
+    pub fn split(&self) -> Option<WorkloadGroup> {
+        self.group_by
+            .iter()
+            .copied()
+            .find(|group| !group.is_period())
+    }
+}
+
+/// Hours planned in one period of a workload
+#[derive(Serialize, Debug, Clone)]
+pub struct WorkloadPeriod {
+    /// First day of the period, the range may start after it
+    pub start: NaiveDate,
+    pub duration: i64,
+    pub cards: i64,
+    /// Hours of each value of the split field, every value listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<BTreeMap<String, i64>>,
+}
+
+/// Hours planned on a range of days, every period of the range listed
+#[derive(Serialize, Debug, Clone)]
+pub struct Workload {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Time zone the days were read in
+    pub time_zone: String,
+    pub period: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split: Option<&'static str>,
+    pub duration: i64,
+    pub periods: Vec<WorkloadPeriod>,
+}
+
+/// Hours planned for one person on a date
+#[derive(Serialize, Debug, Clone)]
+pub struct AssigneeDuration {
+    pub user_id: i64,
+    pub name: String,
+    pub duration: i64,
+}
+
+/// Hours planned on a date, a card counts fully for each of its assignees
+#[derive(Serialize, Debug, Clone)]
+pub struct PlannedDuration {
+    pub date: NaiveDate,
+    /// Time zone the day was read in
+    pub time_zone: String,
+    /// Sum over every card, each one counted once
+    pub total: i64,
+    /// Sum over the cards nobody is assigned to
+    pub unassigned: i64,
+    pub assignees: Vec<AssigneeDuration>,
+}
+
+/// Step of a card checklist
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub card_id: i64,
+    pub text: String,
+    pub done: bool,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Checklist item sent by the client, without `position` it is added at the end
+/// or keeps its place when edited
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChecklistItemData {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "checklist_items"]
+pub struct NewChecklistItem<'a> {
+    pub card_id: i64,
+    pub text: &'a str,
+    pub done: bool,
+    pub position: i32,
+}
+
+/// Completion of a card checklist, such as 3 of 5 items done
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct ChecklistProgress {
+    pub done: i64,
+    pub total: i64,
+}
+
+/// Rolled up fields of the live children of a card
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct ChildRollup {
+    pub count: i64,
+    /// Sum of the children durations, in hours
+    pub duration: i64,
+    /// Share of the children in `completed`, from 0 to 100
+    pub completed_percent: i64,
+}
+
+/// Card with its live descendants
+#[derive(Serialize, Debug, Clone)]
+pub struct CardTree {
+    #[serde(flatten)]
+    pub card: CardView,
+    pub children: Vec<CardTree>,
+}
+
+/// What happens to the children of a deleted card
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChildrenPolicy {
+    /// The children go to the trash with it, and their own children
+    Cascade,
+    /// The children move to the parent of the deleted card, or to the top level
+    Reparent,
+}
+
+/// Options of a card deletion
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CardDeleteQuery {
+    /// Required when the card has live children
+    pub children: Option<ChildrenPolicy>,
+}
+
+/// Field a board adds to its cards, such as a customer or a ticket URL
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct CustomField {
+    pub id: i64,
+    pub board_id: i64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    /// Values a `select` field accepts
+    pub options: Vec<String>,
+    /// Cards cannot be written without a value
+    pub required: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable, AsChangeset, Deserialize)]
+#[table_name = "custom_fields"]
+pub struct CustomFieldData {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Comment of a card thread
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct Comment {
+    pub id: i64,
+    pub card_id: i64,
+    pub author: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CommentData {
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "comments"]
+pub struct NewComment<'a> {
+    pub card_id: i64,
+    pub author: Option<&'a str>,
+    pub body: &'a str,
+}
+
+/// Time zone a route reads days in
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct TimeZoneQuery {
+    pub tz: Option<String>,
+}
+
+/// Card writes going over a daily capacity under the reject policy
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CapacityQuery {
+    /// Let the write go through anyway, with a warning
+    #[serde(default)]
+    pub allow_overbook: bool,
+}
+
+/// Pagination of a list, pages start at 1
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PageQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl PageQuery {
+    /// Page size used when the client does not send one
+    pub const DEFAULT_PER_PAGE: i64 = 20;
+
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(Self::DEFAULT_PER_PAGE)
+    }
+}
+
+/// Page of comments, oldest first
+#[derive(Serialize, Debug, Clone)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+/// File attached to a card, its content is kept in the attachment storage
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct Attachment {
+    pub id: i64,
+    pub card_id: i64,
+    pub filename: String,
+    pub size: i64,
+    pub mime: String,
+    pub sha256: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "attachments"]
+pub struct NewAttachment {
+    pub card_id: i64,
+    pub filename: String,
+    pub size: i64,
+    pub mime: String,
+    pub sha256: String,
+}
+
+/// Cards permanently deleted, with the attachments whose files are left to remove
+#[derive(Debug, Clone, Default)]
+pub struct PurgedCards {
+    pub cards: usize,
+    pub attachment_ids: Vec<i64>,
+}
+
+/// Column of a board workflow, displaying the cards of one status
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct WorkflowColumn {
+    pub status: CardStatus,
+    pub name: String,
+    pub color: String,
+    pub position: i32,
+    /// Most live cards the column holds, unlimited when `None`
+    pub wip_limit: Option<i32>,
+    /// Most live cards of the column assigned to a single user, unlimited when `None`
+    pub assignee_wip_limit: Option<i32>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "workflow_columns"]
+pub struct NewWorkflowColumn<'a> {
+    pub board_id: i64,
+    pub status: CardStatus,
+    pub name: &'a str,
+    pub color: &'a str,
+    pub position: i32,
+    pub wip_limit: Option<i32>,
+    pub assignee_wip_limit: Option<i32>,
+}
+
+/// Move allowed between two columns of a board
+#[derive(Serialize, Deserialize, Debug, Clone, Queryable, Insertable)]
+#[table_name = "workflow_transitions"]
+pub struct WorkflowTransition {
+    #[serde(skip)]
+    pub board_id: i64,
+    #[serde(rename = "from")]
+    pub from_status: CardStatus,
+    #[serde(rename = "to")]
+    pub to_status: CardStatus,
+}
+
+/// Workflow of a board, when `transitions` is empty cards move freely between columns
+#[derive(Serialize, Debug, Clone)]
+pub struct Workflow {
+    pub board_id: i64,
+    pub columns: Vec<WorkflowColumn>,
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+impl Workflow {
+    /// Check a card can be moved from one status to another
+    pub fn allows(&self, from: CardStatus, to: CardStatus) -> bool {
+        from == to
+            || self.transitions.is_empty()
+            || self
+                .transitions
+                .iter()
+                .any(|transition| transition.from_status == from && transition.to_status == to)
+    }
+
+    /// Check a status is displayed by one of the columns
+    pub fn has_column(&self, status: CardStatus) -> bool {
+        self.columns.iter().any(|column| column.status == status)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorkflowColumnData {
+    pub status: CardStatus,
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub wip_limit: Option<i32>,
+    #[serde(default)]
+    pub assignee_wip_limit: Option<i32>,
+}
+
+/// Replacement workflow of a board, columns are displayed in the given order
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorkflowData {
+    pub columns: Vec<WorkflowColumnData>,
+    #[serde(default)]
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+impl WorkflowData {
+    /// Columns every new board starts with
+    pub fn default_columns() -> Vec<WorkflowColumnData> {
+        [
+            (CardStatus::Todo, "To-do", "#FFEB3B"),
+            (CardStatus::InProgress, "In Progress", "#2196F3"),
+            (CardStatus::Completed, "Completed", "#4CAF50"),
+        ]
+        .iter()
+        .map(|(status, name, color)| WorkflowColumnData {
+            status: *status,
+            name: name.to_string(),
+            color: color.to_string(),
+            wip_limit: None,
+            assignee_wip_limit: None,
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn local_date_reads_the_day_in_the_zone() {
+        let late_evening = instant("2023-10-29T22:30:00Z");
+
+        assert_eq!(
+            local_date(late_evening, Some("Europe/Paris")),
+            day(2023, 10, 29)
+        );
+        assert_eq!(
+            local_date(late_evening, Some("Asia/Tokyo")),
+            day(2023, 10, 30)
+        );
+        assert_eq!(
+            local_date(late_evening, Some("America/New_York")),
+            day(2023, 10, 29)
+        );
+    }
+
+    #[test]
+    fn local_date_falls_back_to_utc() {
+        let just_before_midnight = instant("2023-10-29T23:59:59Z");
+
+        assert_eq!(local_date(just_before_midnight, None), day(2023, 10, 29));
+        assert_eq!(
+            local_date(just_before_midnight, Some("Mars/Olympus")),
+            day(2023, 10, 29)
+        );
+    }
+
+    #[test]
+    fn spring_forward_days_are_shorter() {
+        let (start, end) = day_bounds(day(2023, 3, 26), chrono_tz::Europe::Paris);
+
+        assert_eq!(start, instant("2023-03-25T23:00:00Z"));
+        assert_eq!(end, instant("2023-03-26T22:00:00Z"));
+    }
+
+    #[test]
+    fn fall_back_days_are_longer() {
+        let (start, end) = day_bounds(day(2023, 10, 29), chrono_tz::Europe::Paris);
+
+        assert_eq!(start, instant("2023-10-28T22:00:00Z"));
+        assert_eq!(end, instant("2023-10-29T23:00:00Z"));
+    }
+
+    #[test]
+    fn days_skipping_midnight_start_after_the_gap() {
+        // Santiago moves from 00:00 -04 straight to 01:00 -03
+        let zone = chrono_tz::America::Santiago;
+        let (start, end) = day_bounds(day(2023, 9, 3), zone);
+
+        assert_eq!(start, instant("2023-09-03T04:00:00Z"));
+        assert_eq!(end, instant("2023-09-04T03:00:00Z"));
+        assert_eq!(day_bounds(day(2023, 9, 2), zone).1, start);
+    }
+
+    #[test]
+    fn planned_date_bounds_read_in_any_zone() {
+        // Earliest and latest days of check_date, on either side of UTC
+        let (start, _) = day_bounds(day(1970, 1, 1), chrono_tz::Asia::Tokyo);
+        assert_eq!(start, instant("1969-12-31T15:00:00Z"));
+
+        let (_, end) = day_bounds(day(2100, 12, 31), chrono_tz::Pacific::Pago_Pago);
+        assert_eq!(end, instant("2101-01-01T11:00:00Z"));
+    }
+
+    #[test]
+    fn the_last_day_does_not_overflow() {
+        let (start, end) = day_bounds(NaiveDate::MAX, Tz::UTC);
+
+        assert_eq!(
+            start,
+            Utc.from_utc_datetime(&NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap())
+        );
+        assert_eq!(end, DateTime::<Utc>::MAX_UTC);
+    }
+
+    fn patch(json: &str) -> Result<CardPatch, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn absent_fields_are_left_untouched() {
+        let patch = patch("{}").unwrap();
+
+        assert!(patch.is_empty());
+        assert_eq!(patch.due_at, None);
+        assert_eq!(patch.time_zone, None);
+    }
+
+    #[test]
+    fn present_fields_are_set() {
+        let patch = patch(
+            r#"{"title": "Call", "duration": 2, "due_at": "2023-10-29T22:30:00Z", "time_zone": "Europe/Paris"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(patch.title.as_deref(), Some("Call"));
+        assert_eq!(patch.duration, Some(2));
+        assert_eq!(patch.due_at, Some(Some(instant("2023-10-29T22:30:00Z"))));
+        assert_eq!(patch.time_zone, Some(Some("Europe/Paris".to_string())));
+        assert_eq!(patch.description, None);
+    }
+
+    #[test]
+    fn null_clears_nullable_fields() {
+        let patch = patch(r#"{"due_at": null, "time_zone": null}"#).unwrap();
+
+        assert_eq!(patch.due_at, Some(None));
+        assert_eq!(patch.time_zone, Some(None));
+        assert!(!patch.is_empty());
+    }
+
+    #[test]
+    fn null_is_refused_for_required_fields() {
+        for field in [
+            "title",
+            "description",
+            "date",
+            "priority",
+            "duration",
+            "status",
+        ] {
+            assert!(
+                patch(&format!(r#"{{"{}": null}}"#, field)).is_err(),
+                "{} accepted null",
+                field
+            );
+        }
+    }
 }
@@ -2,34 +2,15 @@ use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::error::Error as StdError;
 use std::fmt;
-use thiserror::Error;
 use warp::reject::Reject;
 use warp::{http::StatusCode, Rejection, Reply};
 
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("jwt token not valid")]
-    JWTToken,
-    #[error("jwt token creation error")]
-    JWTTokenCreation,
-    #[error("no auth header")]
-    NoAuthHeader,
-    #[error("invalid auth header")]
-    InvalidAuthHeader,
-    #[error("no permission")]
-    NoPermission,
-    #[error("client not found")]
-    ClientNotFound,
-}
-
 #[derive(Serialize)]
 struct ErrorResponse {
     message: String,
     status: String,
 }
 
-impl warp::reject::Reject for Error {}
-
 /// Handle route rejections
 /// Based on status code
 /// Swagger config:
@@ -47,25 +28,21 @@ pub async fn handle_rejection(err: Rejection) -> std::result::Result<impl Reply,
             }
             ErrorType::CacheError => (warp::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             ErrorType::Unauthorized => (warp::http::StatusCode::UNAUTHORIZED, e.to_string()),
-            ErrorType::MissingRequiredField => (warp::http::StatusCode::BAD_REQUEST, e.to_string()),
+            ErrorType::MissingRequiredField => {
+                (warp::http::StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+            }
             ErrorType::AlreadyExists => (warp::http::StatusCode::CONFLICT, e.to_string()),
+            ErrorType::PreconditionFailed => {
+                (warp::http::StatusCode::PRECONDITION_FAILED, e.to_string())
+            }
+            ErrorType::Conflict => (warp::http::StatusCode::CONFLICT, e.to_string()),
+            ErrorType::PayloadTooLarge => {
+                (warp::http::StatusCode::PAYLOAD_TOO_LARGE, e.to_string())
+            }
+            ErrorType::RangeNotSatisfiable => {
+                (warp::http::StatusCode::RANGE_NOT_SATISFIABLE, e.to_string())
+            }
         }
-    } else if let Some(e) = err.find::<Error>() {
-        match e {
-            Error::NoAuthHeader => (StatusCode::UNAUTHORIZED, e.to_string()),
-            Error::NoPermission => (StatusCode::UNAUTHORIZED, e.to_string()),
-            Error::JWTToken => (StatusCode::UNAUTHORIZED, e.to_string()),
-            Error::JWTTokenCreation => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal Server Error".to_string(),
-            ),
-            Error::InvalidAuthHeader => (StatusCode::UNAUTHORIZED, e.to_string()),
-            Error::ClientNotFound => (StatusCode::NOT_FOUND, e.to_string()),
-            #[allow(unreachable_patterns)]
-            _ => (StatusCode::BAD_REQUEST, e.to_string()),
-        }
-    } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
-        (StatusCode::BAD_REQUEST, "Bad Request".to_string())
     } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
         // This error happens if the body could not be deserialized correctly
         // We can use the cause to analyze the error and customize the error message
@@ -73,6 +50,9 @@ pub async fn handle_rejection(err: Rejection) -> std::result::Result<impl Reply,
             Some(cause) => {
                 if cause.to_string().contains("invalid type") {
                     format!("FIELD_ERROR: invalid type {}", cause)
+                } else if cause.to_string().contains("unknown variant") {
+                    // Enum fields (status, priority) only accept the values the board renders
+                    format!("FIELD_ERROR: {}", cause)
                 } else {
                     "Bad Request".to_string()
                 }
@@ -89,6 +69,10 @@ pub async fn handle_rejection(err: Rejection) -> std::result::Result<impl Reply,
         )
     } else if err.find::<warp::reject::InvalidQuery>().is_some() {
         (StatusCode::BAD_REQUEST, "Missing field".to_string())
+    } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
+        // Checked last: a route that matched but failed to parse its body or query
+        // is reported alongside the method mismatches of the other routes
+        (StatusCode::BAD_REQUEST, "Bad Request".to_string())
     } else {
         eprintln!("unhandled error: {:?}", err);
         (
@@ -115,12 +99,37 @@ pub enum ErrorType {
     Unauthorized,
     MissingRequiredField,
     AlreadyExists,
+    PreconditionFailed,
+    Conflict,
+    PayloadTooLarge,
+    RangeNotSatisfiable,
+}
+
+/// A single failing field of a request payload
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    /// Create new FieldError
+    /// Swagger config:
+    ///   - N/A (constructor)
+    pub fn new(field: &str, message: &str) -> FieldError {
+        FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppError {
     pub err_type: ErrorType,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
 }
 
 impl AppError {
@@ -131,6 +140,18 @@ impl AppError {
         AppError {
             message: message.to_string(),
             err_type,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Create new AppError carrying the fields that caused it
+    /// Swagger config:
+    ///   - N/A (constructor)
+    pub fn with_fields(message: &str, err_type: ErrorType, fields: Vec<FieldError>) -> AppError {
+        AppError {
+            message: message.to_string(),
+            err_type,
+            fields,
         }
     }
 
@@ -139,7 +160,7 @@ impl AppError {
     ///   - N/A (helper function)
     pub fn from_diesel_err(err: diesel::result::Error, context: &str) -> AppError {
         AppError::new(
-            format!("{}: {}", context, err.to_string()).as_str(),
+            format!("{}: {}", context, err).as_str(),
             match err {
                 diesel::result::Error::DatabaseError(
                     diesel::result::DatabaseErrorKind::UniqueViolation,
@@ -151,6 +172,23 @@ impl AppError {
         )
     }
 
+    /// Convert diesel error to app error, a unique violation being reported with `message`
+    /// Swagger config:
+    ///   - N/A (helper function)
+    pub fn from_unique_violation(
+        err: diesel::result::Error,
+        context: &str,
+        message: &str,
+    ) -> AppError {
+        match err {
+            diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            ) => AppError::new(message, ErrorType::AlreadyExists),
+            err => AppError::from_diesel_err(err, context),
+        }
+    }
+
     /// Convert AppError to HTTP status code
     /// Swagger config:
     ///   - N/A (helper function)
@@ -161,13 +199,23 @@ impl AppError {
             ErrorType::DistantServer => warp::http::StatusCode::SERVICE_UNAVAILABLE,
             ErrorType::CacheError => warp::http::StatusCode::INTERNAL_SERVER_ERROR,
             ErrorType::Unauthorized => warp::http::StatusCode::UNAUTHORIZED,
-            ErrorType::MissingRequiredField => warp::http::StatusCode::BAD_REQUEST,
+            ErrorType::MissingRequiredField => warp::http::StatusCode::UNPROCESSABLE_ENTITY,
             ErrorType::AlreadyExists => warp::http::StatusCode::CONFLICT,
+            ErrorType::PreconditionFailed => warp::http::StatusCode::PRECONDITION_FAILED,
+            ErrorType::Conflict => warp::http::StatusCode::CONFLICT,
+            ErrorType::PayloadTooLarge => warp::http::StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorType::RangeNotSatisfiable => warp::http::StatusCode::RANGE_NOT_SATISFIABLE,
             _ => warp::http::StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
 
+impl From<diesel::result::Error> for AppError {
+    fn from(err: diesel::result::Error) -> AppError {
+        AppError::from_diesel_err(err, "while running transaction")
+    }
+}
+
 impl std::error::Error for AppError {}
 
 impl fmt::Display for AppError {
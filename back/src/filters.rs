@@ -1,10 +1,18 @@
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 use serde::de::DeserializeOwned;
-use warp::Filter;
+use warp::{hyper::body::Bytes, reject, Filter};
 
 use crate::{
-    database::{with_db_access_manager, PgPool},
-    domain::CardData,
+    database::{with_db_access_manager, with_pool, with_wip_access_manager, PgPool},
+    domain::{
+        BoardData, CapacityQuery, CardData, CardDeleteQuery, CardListQuery, CardMove, CardPatch,
+        CardTemplateData, ChecklistItemData, CommentData, CustomFieldData, DailyCapacity,
+        DateRangeQuery, LabelData, PageQuery, RecurrenceData, TimeZoneQuery, TimerData, UserData,
+        WorkflowData, WorkloadQuery, DEFAULT_BOARD_ID,
+    },
+    errors::{AppError, ErrorType},
+    storage::{with_storage, Storage},
 };
 
 /// Filter for extracting the JSON body from the request.
@@ -15,16 +23,106 @@ pub fn with_json_body<T: DeserializeOwned + Send>(
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
+/// Filter extracting the time zone days are read in, from the `tz` query parameter
+/// or the `X-Time-Zone` header, UTC when the client sends none.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn with_time_zone() -> impl Filter<Extract = (Tz,), Error = warp::Rejection> + Clone {
+    warp::query::<TimeZoneQuery>()
+        .and(warp::header::optional::<String>("x-time-zone"))
+        .and_then(|query: TimeZoneQuery, header: Option<String>| async move {
+            match query.tz.or(header) {
+                None => Ok(Tz::UTC),
+                Some(name) => name.parse::<Tz>().map_err(|_| {
+                    reject::custom(AppError::new(
+                        format!("`{}` is not an IANA time zone", name).as_str(),
+                        ErrorType::BadRequest,
+                    ))
+                }),
+            }
+        })
+}
+
+/// Filter for extracting a JSON Merge Patch body from the request.
+/// Accepts both `application/json` and `application/merge-patch+json`.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn with_merge_patch_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("content-type")
+        .and_then(|content_type: Option<String>| async move {
+            match content_type
+                .as_deref()
+                .map(|value| value.split(';').next().unwrap_or("").trim())
+            {
+                None | Some("application/json") | Some("application/merge-patch+json") => Ok(()),
+                Some(_) => Err(reject::custom(AppError::new(
+                    "unsupported content type, expected application/merge-patch+json",
+                    ErrorType::BadRequest,
+                ))),
+            }
+        })
+        .untuple_one()
+        .and(warp::body::content_length_limit(1024 * 16))
+        .and(warp::body::bytes())
+        .and_then(|body: Bytes| async move {
+            serde_json::from_slice::<T>(&body).map_err(|err| {
+                reject::custom(AppError::new(
+                    format!("FIELD_ERROR: {}", err).as_str(),
+                    ErrorType::BadRequest,
+                ))
+            })
+        })
+}
+
+/// Filter for extracting the card version from an optional `If-Match` header.
+/// `*` matches any version, like an absent header.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn with_if_match() -> impl Filter<Extract = (Option<i32>,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("if-match").and_then(|value: Option<String>| async move {
+        match value.as_deref().map(str::trim) {
+            None | Some("*") => Ok(None),
+            Some(tag) => tag
+                .strip_prefix('"')
+                .and_then(|tag| tag.strip_suffix('"'))
+                .and_then(|version| version.parse::<i32>().ok())
+                .map(Some)
+                .ok_or_else(|| {
+                    reject::custom(AppError::new(
+                        "invalid If-Match header, expected a card ETag such as \"3\"",
+                        ErrorType::BadRequest,
+                    ))
+                }),
+        }
+    })
+}
+
+/// Filter passing the server default of the daily capacities to the card writes.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn with_capacity(
+    capacity: DailyCapacity,
+) -> impl Filter<Extract = (DailyCapacity,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || capacity)
+}
+
 /// Route for creating a card.
 /// Swagger config:
 ///   - Path: "/card"
 ///   - Method: POST
 pub fn route_create_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("card")
+    board
+        .and(warp::path!("card"))
         .and(warp::post())
-        .and(with_db_access_manager(pool))
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
         .and(with_json_body::<CardData>())
         .and_then(super::card::create_card)
 }
@@ -34,36 +132,174 @@ pub fn route_create_card(
 ///   - Path: "/card/{id}"
 ///   - Method: PUT
 pub fn route_edit_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("card" / i64)
+    board
+        .and(warp::path!("card" / i64))
         .and(warp::put())
-        .and(with_db_access_manager(pool))
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(with_if_match())
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
         .and(with_json_body::<CardData>())
         .and_then(super::card::edit_card)
 }
 
+/// Route for partially updating a card.
+/// Swagger config:
+///   - Path: "/card/{id}"
+///   - Method: PATCH
+pub fn route_patch_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64))
+        .and(warp::patch())
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(with_if_match())
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
+        .and(with_merge_patch_body::<CardPatch>())
+        .and_then(super::card::patch_card)
+}
+
+/// Route for moving a card to a position of a column.
+/// Swagger config:
+///   - Path: "/card/{id}/move"
+///   - Method: POST
+pub fn route_move_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    admin_token: Option<String>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "move"))
+        .and(warp::post())
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(with_if_match())
+        .and(with_json_body::<CardMove>())
+        .and_then(super::card::move_card)
+}
+
 /// Route for deleting a card.
 /// Swagger config:
 ///   - Path: "/card/{id}"
 ///   - Method: DELETE
 pub fn route_delete_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("card" / i64)
+    board
+        .and(warp::path!("card" / i64))
         .and(warp::delete())
         .and(with_db_access_manager(pool))
+        .and(with_if_match())
+        .and(warp::query::<CardDeleteQuery>())
         .and_then(super::card::delete_card)
 }
 
+/// Route for restoring a card from the trash.
+/// Swagger config:
+///   - Path: "/card/{id}/restore"
+///   - Method: POST
+pub fn route_restore_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "restore"))
+        .and(warp::post())
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
+        .and_then(super::card::restore_card)
+}
+
+/// Route for getting the revision history of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/history"
+///   - Method: GET
+pub fn route_get_card_history(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "history"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::history::get_card_history)
+}
+
+/// Route for reverting a card to a previous revision.
+/// Swagger config:
+///   - Path: "/card/{id}/revert/{revision}"
+///   - Method: POST
+pub fn route_revert_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "revert" / i32))
+        .and(warp::post())
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(with_if_match())
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
+        .and_then(super::history::revert_card)
+}
+
+/// Route for listing the trash.
+/// Swagger config:
+///   - Path: "/trash"
+///   - Method: GET
+pub fn route_get_trash(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("trash"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::trash::get_trash)
+}
+
+/// Route for permanently deleting a card from the trash.
+/// Swagger config:
+///   - Path: "/trash/{id}"
+///   - Method: DELETE
+pub fn route_purge_card(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    storage: Storage,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("trash" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and(with_storage(storage))
+        .and_then(super::trash::purge_card)
+}
+
 /// Route for getting a card by ID.
 /// Swagger config:
 ///   - Path: "/card/{id}"
 ///   - Method: GET
 pub fn route_get_card_by_id(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("card" / i64)
+    board
+        .and(warp::path!("card" / i64))
         .and(warp::get())
         .and(with_db_access_manager(pool))
         .and_then(super::card::get_card_by_id)
@@ -74,43 +310,1148 @@ pub fn route_get_card_by_id(
 ///   - Path: "/card/check/{date}"
 ///   - Method: GET
 pub fn check_total_duration_on_date(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("card" / "check" / NaiveDate)
+    board
+        .and(warp::path!("card" / "check" / NaiveDate))
         .and(warp::get())
         .and(with_db_access_manager(pool))
+        .and(with_time_zone())
         .and_then(super::card::check_total_duration_on_date)
 }
 
+/// Route for comparing the estimated and tracked time of a range of days.
+/// Swagger config:
+///   - Path: "/card/report"
+///   - Method: GET
+pub fn route_effort_report(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / "report"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and(warp::query::<DateRangeQuery>())
+        .and(with_time_zone())
+        .and_then(super::card::effort_report)
+}
+
+/// Route for the hours planned on a range of days.
+/// Swagger config:
+///   - Path: "/card/workload"
+///   - Method: GET
+pub fn route_get_workload(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / "workload"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and(warp::query::<WorkloadQuery>())
+        .and(with_time_zone())
+        .and_then(super::card::get_workload)
+}
+
 /// Route for getting all cards.
 /// Swagger config:
 ///   - Path: "/card"
 ///   - Method: GET
 pub fn route_get_all_cards(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("card")
+    board
+        .and(warp::path!("card"))
         .and(warp::get())
         .and(with_db_access_manager(pool))
+        .and(warp::query::<CardListQuery>())
         .and_then(super::card::get_all_cards)
 }
 
-/// Aggregates Warp Filters for the API routes.
+/// Route for getting the workflow of a board.
 /// Swagger config:
-///   - Path: "/api"
-///   - Sub-paths:
-///     - "/card" (POST, PUT, DELETE, GET)
-///     - "/card/{id}" (GET)
-///     - "/card/check/{date}" (GET)
-pub fn api_filters(
+///   - Path: "/workflow"
+///   - Method: GET
+pub fn route_get_workflow(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
     pool: PgPool,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("api" / ..).and(
-        route_create_card(pool.clone())
-            .or(route_edit_card(pool.clone()))
-            .or(route_delete_card(pool.clone()))
-            .or(route_get_all_cards(pool.clone()))
-            .or(route_get_card_by_id(pool.clone()))
-            .or(check_total_duration_on_date(pool.clone())),
-    )
+    board
+        .and(warp::path!("workflow"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::workflow::get_workflow)
+}
+
+/// Route for replacing the workflow of a board.
+/// Swagger config:
+///   - Path: "/workflow"
+///   - Method: PUT
+pub fn route_replace_workflow(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("workflow"))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<WorkflowData>())
+        .and_then(super::workflow::replace_workflow)
+}
+
+/// Route for creating a label.
+/// Swagger config:
+///   - Path: "/label"
+///   - Method: POST
+pub fn route_create_label(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("label"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<LabelData>())
+        .and_then(super::label::create_label)
+}
+
+/// Route for listing the labels of a board.
+/// Swagger config:
+///   - Path: "/label"
+///   - Method: GET
+pub fn route_get_labels(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("label"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::label::get_labels)
+}
+
+/// Route for editing a label.
+/// Swagger config:
+///   - Path: "/label/{id}"
+///   - Method: PUT
+pub fn route_edit_label(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("label" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<LabelData>())
+        .and_then(super::label::edit_label)
+}
+
+/// Route for deleting a label.
+/// Swagger config:
+///   - Path: "/label/{id}"
+///   - Method: DELETE
+pub fn route_delete_label(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("label" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::label::delete_label)
+}
+
+/// Route for attaching a label to a card.
+/// Swagger config:
+///   - Path: "/card/{id}/label/{label_id}"
+///   - Method: PUT
+pub fn route_attach_label(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "label" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and_then(super::label::attach_label)
+}
+
+/// Route for detaching a label from a card.
+/// Swagger config:
+///   - Path: "/card/{id}/label/{label_id}"
+///   - Method: DELETE
+pub fn route_detach_label(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "label" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::label::detach_label)
+}
+
+/// Route for getting the checklist of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/checklist"
+///   - Method: GET
+pub fn route_get_checklist(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "checklist"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::checklist::get_checklist)
+}
+
+/// Route for adding an item to the checklist of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/checklist"
+///   - Method: POST
+pub fn route_create_checklist_item(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "checklist"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<ChecklistItemData>())
+        .and_then(super::checklist::create_checklist_item)
+}
+
+/// Route for editing an item of the checklist of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/checklist/{item_id}"
+///   - Method: PUT
+pub fn route_edit_checklist_item(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "checklist" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<ChecklistItemData>())
+        .and_then(super::checklist::edit_checklist_item)
+}
+
+/// Route for deleting an item of the checklist of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/checklist/{item_id}"
+///   - Method: DELETE
+pub fn route_delete_checklist_item(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "checklist" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::checklist::delete_checklist_item)
+}
+
+/// Route for listing the comments of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/comments"
+///   - Method: GET
+pub fn route_get_comments(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "comments"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and(warp::query::<PageQuery>())
+        .and_then(super::comment::get_comments)
+}
+
+/// Route for commenting a card.
+/// Swagger config:
+///   - Path: "/card/{id}/comments"
+///   - Method: POST
+pub fn route_create_comment(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "comments"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<CommentData>())
+        .and_then(super::comment::create_comment)
+}
+
+/// Route for editing a comment of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/comments/{comment_id}"
+///   - Method: PUT
+pub fn route_edit_comment(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "comments" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<CommentData>())
+        .and_then(super::comment::edit_comment)
+}
+
+/// Route for deleting a comment of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/comments/{comment_id}"
+///   - Method: DELETE
+pub fn route_delete_comment(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "comments" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::comment::delete_comment)
+}
+
+/// Route for uploading files to a card.
+/// The only route accepting a body larger than 16 KB, up to the storage limit.
+/// Swagger config:
+///   - Path: "/card/{id}/attachments"
+///   - Method: POST
+pub fn route_upload_attachments(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    storage: Storage,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let max_size = storage.max_size;
+
+    board
+        .and(warp::path!("card" / i64 / "attachments"))
+        .and(warp::post())
+        .and(with_pool(pool))
+        .and(with_storage(storage))
+        .and(warp::multipart::form().max_length(max_size))
+        .and_then(super::attachment::upload_attachments)
+}
+
+/// Route for listing the attachments of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/attachments"
+///   - Method: GET
+pub fn route_get_attachments(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "attachments"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::attachment::get_attachments)
+}
+
+/// Route for downloading an attachment.
+/// Swagger config:
+///   - Path: "/attachments/{id}"
+///   - Method: GET
+pub fn route_download_attachment(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    storage: Storage,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("attachments" / i64))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and(with_storage(storage))
+        .and(warp::header::optional::<String>("range"))
+        .and_then(super::attachment::download_attachment)
+}
+
+/// Route for deleting an attachment.
+/// Swagger config:
+///   - Path: "/attachments/{id}"
+///   - Method: DELETE
+pub fn route_delete_attachment(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    storage: Storage,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("attachments" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and(with_storage(storage))
+        .and_then(super::attachment::delete_attachment)
+}
+
+/// Route for assigning a user to a card.
+/// Swagger config:
+///   - Path: "/card/{id}/assignee/{user_id}"
+///   - Method: PUT
+pub fn route_assign_user(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "assignee" / i64))
+        .and(warp::put())
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
+        .and_then(super::user::assign_user)
+}
+
+/// Route for unassigning a user from a card.
+/// Swagger config:
+///   - Path: "/card/{id}/assignee/{user_id}"
+///   - Method: DELETE
+pub fn route_unassign_user(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "assignee" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::user::unassign_user)
+}
+
+/// Route for making a card wait for another one.
+/// Swagger config:
+///   - Path: "/card/{id}/blocked_by/{blocker_id}"
+///   - Method: PUT
+pub fn route_add_blocker(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "blocked_by" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and_then(super::dependency::add_blocker)
+}
+
+/// Route for removing a blocker of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/blocked_by/{blocker_id}"
+///   - Method: DELETE
+pub fn route_remove_blocker(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "blocked_by" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::dependency::remove_blocker)
+}
+
+/// Route for getting the recurrence of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/recurrence"
+///   - Method: GET
+pub fn route_get_recurrence(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "recurrence"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::recurrence::get_recurrence)
+}
+
+/// Route for making a card repeat.
+/// Swagger config:
+///   - Path: "/card/{id}/recurrence"
+///   - Method: PUT
+pub fn route_set_recurrence(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "recurrence"))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<RecurrenceData>())
+        .and_then(super::recurrence::set_recurrence)
+}
+
+/// Route for stopping a card from repeating.
+/// Swagger config:
+///   - Path: "/card/{id}/recurrence"
+///   - Method: DELETE
+pub fn route_delete_recurrence(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "recurrence"))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::recurrence::delete_recurrence)
+}
+
+/// Route for getting the time entries of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/time_entries"
+///   - Method: GET
+pub fn route_get_time_entries(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "time_entries"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::time_entry::get_time_entries)
+}
+
+/// Route for starting a timer on a card.
+/// Swagger config:
+///   - Path: "/card/{id}/timer/start"
+///   - Method: POST
+pub fn route_start_timer(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "timer" / "start"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<TimerData>())
+        .and_then(super::time_entry::start_timer)
+}
+
+/// Route for stopping a timer on a card.
+/// Swagger config:
+///   - Path: "/card/{id}/timer/stop"
+///   - Method: POST
+pub fn route_stop_timer(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "timer" / "stop"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<TimerData>())
+        .and_then(super::time_entry::stop_timer)
+}
+
+/// Route for creating a card template.
+/// Swagger config:
+///   - Path: "/template"
+///   - Method: POST
+pub fn route_create_template(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("template"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<CardTemplateData>())
+        .and_then(super::template::create_template)
+}
+
+/// Route for listing the card templates of a board.
+/// Swagger config:
+///   - Path: "/template"
+///   - Method: GET
+pub fn route_get_templates(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("template"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::template::get_templates)
+}
+
+/// Route for getting a card template.
+/// Swagger config:
+///   - Path: "/template/{id}"
+///   - Method: GET
+pub fn route_get_template(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("template" / i64))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::template::get_template)
+}
+
+/// Route for editing a card template.
+/// Swagger config:
+///   - Path: "/template/{id}"
+///   - Method: PUT
+pub fn route_edit_template(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("template" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<CardTemplateData>())
+        .and_then(super::template::edit_template)
+}
+
+/// Route for deleting a card template.
+/// Swagger config:
+///   - Path: "/template/{id}"
+///   - Method: DELETE
+pub fn route_delete_template(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("template" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::template::delete_template)
+}
+
+/// Route for creating a card from a template.
+/// Swagger config:
+///   - Path: "/card/from-template/{id}"
+///   - Method: POST
+pub fn route_create_card_from_template(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+    admin_token: Option<String>,
+    capacity: DailyCapacity,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / "from-template" / i64))
+        .and(warp::post())
+        .and(with_wip_access_manager(pool, admin_token))
+        .and(with_time_zone())
+        .and(warp::query::<CapacityQuery>())
+        .and(with_capacity(capacity))
+        .and_then(super::template::create_card_from_template)
+}
+
+/// Route for making a card the child of another.
+/// Swagger config:
+///   - Path: "/card/{id}/parent/{parent_id}"
+///   - Method: PUT
+pub fn route_set_parent(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "parent" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_if_match())
+        .and_then(super::hierarchy::set_parent)
+}
+
+/// Route for moving a card back to the top level.
+/// Swagger config:
+///   - Path: "/card/{id}/parent"
+///   - Method: DELETE
+pub fn route_remove_parent(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "parent"))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and(with_if_match())
+        .and_then(super::hierarchy::remove_parent)
+}
+
+/// Route for listing the children of a card.
+/// Swagger config:
+///   - Path: "/card/{id}/children"
+///   - Method: GET
+pub fn route_get_children(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "children"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::hierarchy::get_children)
+}
+
+/// Route for getting a card with all its descendants.
+/// Swagger config:
+///   - Path: "/card/{id}/subtree"
+///   - Method: GET
+pub fn route_get_subtree(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("card" / i64 / "subtree"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::hierarchy::get_subtree)
+}
+
+/// Route for creating a custom field.
+/// Swagger config:
+///   - Path: "/custom_field"
+///   - Method: POST
+pub fn route_create_custom_field(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("custom_field"))
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<CustomFieldData>())
+        .and_then(super::custom_field::create_custom_field)
+}
+
+/// Route for listing the custom fields of a board.
+/// Swagger config:
+///   - Path: "/custom_field"
+///   - Method: GET
+pub fn route_get_custom_fields(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("custom_field"))
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::custom_field::get_custom_fields)
+}
+
+/// Route for editing a custom field.
+/// Swagger config:
+///   - Path: "/custom_field/{id}"
+///   - Method: PUT
+pub fn route_edit_custom_field(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("custom_field" / i64))
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<CustomFieldData>())
+        .and_then(super::custom_field::edit_custom_field)
+}
+
+/// Route for deleting a custom field.
+/// Swagger config:
+///   - Path: "/custom_field/{id}"
+///   - Method: DELETE
+pub fn route_delete_custom_field(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone,
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    board
+        .and(warp::path!("custom_field" / i64))
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::custom_field::delete_custom_field)
+}
+
+/// Route for creating a board.
+/// Swagger config:
+///   - Path: "/board"
+///   - Method: POST
+pub fn route_create_board(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("board")
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<BoardData>())
+        .and_then(super::board::create_board)
+}
+
+/// Route for getting all boards.
+/// Swagger config:
+///   - Path: "/board"
+///   - Method: GET
+pub fn route_get_all_boards(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("board")
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::board::get_all_boards)
+}
+
+/// Route for getting a board by ID.
+/// Swagger config:
+///   - Path: "/board/{id}"
+///   - Method: GET
+pub fn route_get_board_by_id(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("board" / i64)
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::board::get_board_by_id)
+}
+
+/// Route for editing a board.
+/// Swagger config:
+///   - Path: "/board/{id}"
+///   - Method: PUT
+pub fn route_edit_board(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("board" / i64)
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<BoardData>())
+        .and_then(super::board::edit_board)
+}
+
+/// Route for deleting a board.
+/// Swagger config:
+///   - Path: "/board/{id}"
+///   - Method: DELETE
+pub fn route_delete_board(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("board" / i64)
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::board::delete_board)
+}
+
+/// Route for creating a user.
+/// Swagger config:
+///   - Path: "/user"
+///   - Method: POST
+pub fn route_create_user(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("user")
+        .and(warp::post())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<UserData>())
+        .and_then(super::user::create_user)
+}
+
+/// Route for getting all users.
+/// Swagger config:
+///   - Path: "/user"
+///   - Method: GET
+pub fn route_get_all_users(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("user")
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::user::get_all_users)
+}
+
+/// Route for getting a user by ID.
+/// Swagger config:
+///   - Path: "/user/{id}"
+///   - Method: GET
+pub fn route_get_user_by_id(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("user" / i64)
+        .and(warp::get())
+        .and(with_db_access_manager(pool))
+        .and_then(super::user::get_user_by_id)
+}
+
+/// Route for editing a user.
+/// Swagger config:
+///   - Path: "/user/{id}"
+///   - Method: PUT
+pub fn route_edit_user(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("user" / i64)
+        .and(warp::put())
+        .and(with_db_access_manager(pool))
+        .and(with_json_body::<UserData>())
+        .and_then(super::user::edit_user)
+}
+
+/// Route for deleting a user.
+/// Swagger config:
+///   - Path: "/user/{id}"
+///   - Method: DELETE
+pub fn route_delete_user(
+    pool: PgPool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("user" / i64)
+        .and(warp::delete())
+        .and(with_db_access_manager(pool))
+        .and_then(super::user::delete_user)
+}
+
+/// Filter extracting the board of the card routes nested under "/board/{board_id}".
+/// Swagger config:
+///   - N/A (helper function)
+pub fn with_board_path() -> impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone {
+    warp::path!("board" / i64 / ..)
+}
+
+/// Filter binding the card routes that are not nested under a board to the default one.
+/// Swagger config:
+///   - N/A (helper function)
+pub fn with_default_board() -> impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone {
+    warp::any().and_then(|| async { Ok::<_, warp::Rejection>(DEFAULT_BOARD_ID) })
+}
+
+/// Aggregates the card routes of a board.
+/// Swagger config:
+///   - Sub-paths:
+///     - "/card" (POST, GET)
+///     - "/card/{id}" (GET, PUT, PATCH, DELETE)
+///     - "/card/{id}/move" (POST)
+///     - "/card/check/{date}" (GET)
+///     - "/card/report" (GET)
+///     - "/card/workload" (GET)
+///     - "/card/{id}/restore" (POST)
+///     - "/card/{id}/history" (GET)
+///     - "/card/{id}/revert/{revision}" (POST)
+///     - "/trash" (GET)
+///     - "/trash/{id}" (DELETE)
+///     - "/workflow" (GET, PUT)
+///     - "/label" (POST, GET)
+///     - "/label/{id}" (PUT, DELETE)
+///     - "/card/{id}/label/{label_id}" (PUT, DELETE)
+///     - "/card/{id}/assignee/{user_id}" (PUT, DELETE)
+///     - "/card/{id}/blocked_by/{blocker_id}" (PUT, DELETE)
+///     - "/card/{id}/recurrence" (GET, PUT, DELETE)
+///     - "/card/{id}/parent/{parent_id}" (PUT)
+///     - "/card/{id}/parent" (DELETE)
+///     - "/card/{id}/children" (GET)
+///     - "/card/{id}/subtree" (GET)
+///     - "/card/{id}/time_entries" (GET)
+///     - "/card/{id}/timer/start" (POST)
+///     - "/card/{id}/timer/stop" (POST)
+///     - "/template" (POST, GET)
+///     - "/template/{id}" (GET, PUT, DELETE)
+///     - "/card/from-template/{id}" (POST)
+///     - "/custom_field" (POST, GET)
+///     - "/custom_field/{id}" (PUT, DELETE)
+///     - "/card/{id}/checklist" (GET, POST)
+///     - "/card/{id}/checklist/{item_id}" (PUT, DELETE)
+///     - "/card/{id}/comments" (GET, POST)
+///     - "/card/{id}/comments/{comment_id}" (PUT, DELETE)
+///     - "/card/{id}/attachments" (GET, POST)
+///     - "/attachments/{id}" (GET, DELETE)
+pub fn card_filters(
+    board: impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone + Send + Sync + 'static,
+    pool: PgPool,
+    storage: Storage,
+    capacity: DailyCapacity,
+    admin_token: Option<String>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    route_create_card(board.clone(), pool.clone(), admin_token.clone(), capacity)
+        .or(route_edit_card(
+            board.clone(),
+            pool.clone(),
+            admin_token.clone(),
+            capacity,
+        ))
+        .or(route_patch_card(
+            board.clone(),
+            pool.clone(),
+            admin_token.clone(),
+            capacity,
+        ))
+        .or(route_move_card(
+            board.clone(),
+            pool.clone(),
+            admin_token.clone(),
+        ))
+        .or(route_delete_card(board.clone(), pool.clone()))
+        .or(route_get_all_cards(board.clone(), pool.clone()))
+        .or(route_get_card_by_id(board.clone(), pool.clone()))
+        .or(check_total_duration_on_date(board.clone(), pool.clone()))
+        .or(route_effort_report(board.clone(), pool.clone()))
+        .or(route_get_workload(board.clone(), pool.clone()))
+        .or(route_restore_card(
+            board.clone(),
+            pool.clone(),
+            admin_token.clone(),
+            capacity,
+        ))
+        .or(route_get_card_history(board.clone(), pool.clone()))
+        .or(route_revert_card(
+            board.clone(),
+            pool.clone(),
+            admin_token.clone(),
+            capacity,
+        ))
+        .or(route_get_trash(board.clone(), pool.clone()))
+        .or(route_purge_card(
+            board.clone(),
+            pool.clone(),
+            storage.clone(),
+        ))
+        .or(route_get_workflow(board.clone(), pool.clone()))
+        .or(route_replace_workflow(board.clone(), pool.clone()))
+        .or(route_create_label(board.clone(), pool.clone()))
+        .or(route_get_labels(board.clone(), pool.clone()))
+        .or(route_edit_label(board.clone(), pool.clone()))
+        .or(route_delete_label(board.clone(), pool.clone()))
+        .or(route_attach_label(board.clone(), pool.clone()))
+        .or(route_detach_label(board.clone(), pool.clone()))
+        .or(route_assign_user(
+            board.clone(),
+            pool.clone(),
+            admin_token.clone(),
+            capacity,
+        ))
+        .or(route_unassign_user(board.clone(), pool.clone()))
+        .or(route_add_blocker(board.clone(), pool.clone()))
+        .or(route_remove_blocker(board.clone(), pool.clone()))
+        .or(route_get_recurrence(board.clone(), pool.clone()))
+        .or(route_set_recurrence(board.clone(), pool.clone()))
+        .or(route_delete_recurrence(board.clone(), pool.clone()))
+        .or(route_set_parent(board.clone(), pool.clone()))
+        .or(route_remove_parent(board.clone(), pool.clone()))
+        .or(route_get_children(board.clone(), pool.clone()))
+        .or(route_get_subtree(board.clone(), pool.clone()))
+        .or(route_get_time_entries(board.clone(), pool.clone()))
+        .or(route_start_timer(board.clone(), pool.clone()))
+        .or(route_stop_timer(board.clone(), pool.clone()))
+        .or(route_create_template(board.clone(), pool.clone()))
+        .or(route_get_templates(board.clone(), pool.clone()))
+        .or(route_get_template(board.clone(), pool.clone()))
+        .or(route_edit_template(board.clone(), pool.clone()))
+        .or(route_delete_template(board.clone(), pool.clone()))
+        .or(route_create_card_from_template(
+            board.clone(),
+            pool.clone(),
+            admin_token,
+            capacity,
+        ))
+        .or(route_create_custom_field(board.clone(), pool.clone()))
+        .or(route_get_custom_fields(board.clone(), pool.clone()))
+        .or(route_edit_custom_field(board.clone(), pool.clone()))
+        .or(route_delete_custom_field(board.clone(), pool.clone()))
+        .or(route_get_checklist(board.clone(), pool.clone()))
+        .or(route_create_checklist_item(board.clone(), pool.clone()))
+        .or(route_edit_checklist_item(board.clone(), pool.clone()))
+        .or(route_delete_checklist_item(board.clone(), pool.clone()))
+        .or(route_get_comments(board.clone(), pool.clone()))
+        .or(route_create_comment(board.clone(), pool.clone()))
+        .or(route_edit_comment(board.clone(), pool.clone()))
+        .or(route_delete_comment(board.clone(), pool.clone()))
+        .or(route_upload_attachments(
+            board.clone(),
+            pool.clone(),
+            storage.clone(),
+        ))
+        .or(route_get_attachments(board.clone(), pool.clone()))
+        .or(route_download_attachment(
+            board.clone(),
+            pool.clone(),
+            storage.clone(),
+        ))
+        .or(route_delete_attachment(board, pool, storage))
+}
+
+/// Aggregates Warp Filters for the API routes.
+/// Swagger config:
+///   - Path: "/api"
+///   - Sub-paths:
+///     - "/board" (POST, GET)
+///     - "/board/{id}" (GET, PUT, DELETE)
+///     - "/user" (POST, GET)
+///     - "/user/{id}" (GET, PUT, DELETE)
+///     - "/board/{board_id}/..." (card routes of the board)
+///     - "/..." (card routes of the default board)
+pub fn api_filters(
+    pool: PgPool,
+    storage: Storage,
+    capacity: DailyCapacity,
+    admin_token: Option<String>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("api" / ..).and(
+        route_create_board(pool.clone())
+            .or(route_get_all_boards(pool.clone()))
+            .or(route_get_board_by_id(pool.clone()))
+            .or(route_edit_board(pool.clone()))
+            .or(route_delete_board(pool.clone()))
+            .or(route_create_user(pool.clone()))
+            .or(route_get_all_users(pool.clone()))
+            .or(route_get_user_by_id(pool.clone()))
+            .or(route_edit_user(pool.clone()))
+            .or(route_delete_user(pool.clone()))
+            .or(card_filters(
+                with_board_path(),
+                pool.clone(),
+                storage.clone(),
+                capacity,
+                admin_token.clone(),
+            ))
+            .or(card_filters(
+                with_default_board(),
+                pool,
+                storage,
+                capacity,
+                admin_token,
+            )),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn if_match(value: Option<&str>) -> Result<Option<i32>, warp::Rejection> {
+        let request = warp::test::request();
+        let request = match value {
+            Some(value) => request.header("if-match", value),
+            None => request,
+        };
+        request.filter(&with_if_match()).await
+    }
+
+    fn is_bad_request(rejection: warp::Rejection) -> bool {
+        rejection
+            .find::<AppError>()
+            .is_some_and(|err| matches!(err.err_type, ErrorType::BadRequest))
+    }
+
+    #[tokio::test]
+    async fn missing_or_wildcard_if_match_skips_the_check() {
+        assert_eq!(if_match(None).await.unwrap(), None);
+        assert_eq!(if_match(Some("*")).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn quoted_if_match_gives_the_version() {
+        assert_eq!(if_match(Some("\"3\"")).await.unwrap(), Some(3));
+        assert_eq!(if_match(Some(" \"12\" ")).await.unwrap(), Some(12));
+    }
+
+    #[tokio::test]
+    async fn unquoted_if_match_is_refused() {
+        assert!(is_bad_request(if_match(Some("3")).await.unwrap_err()));
+    }
+
+    #[tokio::test]
+    async fn non_numeric_if_match_is_refused() {
+        assert!(is_bad_request(if_match(Some("\"abc\"")).await.unwrap_err()));
+        assert!(is_bad_request(if_match(Some("W/\"3\"")).await.unwrap_err()));
+    }
 }
@@ -0,0 +1,103 @@
+use crate::{
+    card::{respond, respond_card},
+    database::DBAccessManager,
+    domain::CardView,
+};
+
+/// Make a card the child of another card of the board.
+/// Swagger config:
+///   - Operation ID: set_parent
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/parent/{parent_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: parent_id (integer, card of the same board)
+///   - Header: If-Match (card ETag, optional)
+///   - Response: JSON representation of the card with its parent, with its ETag
+///   - Error 400: a card cannot be its own parent
+///   - Error 409: the parent is a descendant of the card
+///   - Error 412: the card changed since the ETag was read
+pub async fn set_parent(
+    _board_id: i64,
+    _id: i64,
+    _parent_id: i64,
+    _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.set_parent(_board_id, _id, _parent_id, _if_match) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
+
+/// Move a card back to the top level, its children stay with it.
+/// Swagger config:
+///   - Operation ID: remove_parent
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/parent
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Header: If-Match (card ETag, optional)
+///   - Response: JSON representation of the card, with its ETag
+///   - Error 412: the card changed since the ETag was read
+pub async fn remove_parent(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.remove_parent(_board_id, _id, _if_match) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
+
+/// Get the children of a card.
+/// Swagger config:
+///   - Operation ID: get_children
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/children
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON list of the live children, by column then rank
+pub async fn get_children(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_children(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get a card with all its descendants.
+/// Swagger config:
+///   - Operation ID: get_subtree
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/subtree
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON CardTree, the card with its live children nested under it, recursively
+pub async fn get_subtree(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_subtree(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    card::{capacity_warnings, respond, respond_card, respond_checked},
+    database::DBAccessManager,
+    domain::{CapacityQuery, Card, DailyCapacity},
+};
+
+/// Get the revision history of a card.
+/// Swagger config:
+///   - Operation ID: get_card_history
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/history
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON list of the card revisions, most recent first
+pub async fn get_card_history(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_card_history(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Revert a card to one of its revisions.
+/// Swagger config:
+///   - Operation ID: revert_card
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/revert/{revision}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: revision (integer, card version to go back to)
+///   - Header: If-Match (card ETag, optional)
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
+///   - Response: JSON representation of the reverted card, with its ETag
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day goes over a daily capacity under the reject policy
+///   - Error 409: another occurrence of the card's recurrence is already planned on the new date
+///   - Error 412: the card changed since the ETag was read
+///   - Error 422: the custom values of the revision no longer fit the custom fields of the board
+pub async fn revert_card(
+    _board_id: i64,
+    _id: i64,
+    _revision: i32,
+    _db_manager: DBAccessManager,
+    _if_match: Option<i32>,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    match _db_manager.revert_card(_board_id, _id, _revision, _if_match) {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch);
+            respond_checked(_branch, warnings, warp::http::StatusCode::OK)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<Card>(Err(err), status)
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    card::{respond, respond_card},
+    database::DBAccessManager,
+    domain::{CardView, Label, LabelData},
+    validation::Validate,
+};
+
+/// Create a new label.
+/// Swagger config:
+///   - Operation ID: create_label
+///   - HTTP Method: POST
+///   - Path: /labels
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Request Body: LabelData
+///   - Response: JSON representation of the created label
+///   - Error 409: the board already has a label with this name
+///   - Error 422: list of the fields that failed validation
+pub async fn create_label(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: LabelData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Label>(Err(err), status);
+    }
+
+    match _db_manager.create_label(_board_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get all labels of a board.
+/// Swagger config:
+///   - Operation ID: get_labels
+///   - HTTP Method: GET
+///   - Path: /labels
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Response: JSON representation of the labels, sorted by name
+pub async fn get_labels(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_labels(_board_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit an existing label.
+/// Swagger config:
+///   - Operation ID: edit_label
+///   - HTTP Method: PUT
+///   - Path: /labels/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: LabelData
+///   - Response: JSON representation of the edited label
+///   - Error 409: the board already has a label with this name
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_label(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: LabelData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Label>(Err(err), status);
+    }
+
+    match _db_manager.edit_label(_board_id, _id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete a label, detaching it from every card.
+/// Swagger config:
+///   - Operation ID: delete_label
+///   - HTTP Method: DELETE
+///   - Path: /labels/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted labels
+pub async fn delete_label(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_label(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Attach a label to a card.
+/// Swagger config:
+///   - Operation ID: attach_label
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/labels/{label_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: label_id (integer, label of the same board)
+///   - Response: JSON representation of the card with its labels, with its ETag
+pub async fn attach_label(
+    _board_id: i64,
+    _id: i64,
+    _label_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.attach_label(_board_id, _id, _label_id) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
+
+/// Detach a label from a card.
+/// Swagger config:
+///   - Operation ID: detach_label
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/labels/{label_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: label_id (integer)
+///   - Response: JSON representation of the card with its labels, with its ETag
+pub async fn detach_label(
+    _board_id: i64,
+    _id: i64,
+    _label_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.detach_label(_board_id, _id, _label_id) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
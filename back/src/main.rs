@@ -1,26 +1,98 @@
 #![recursion_limit = "256"]
+// diesel 1.x derives and `table!` expand to impls inside anonymous consts
+#![allow(non_local_definitions)]
 extern crate openssl;
 #[macro_use]
 extern crate diesel;
 
 use crate::{
     database::{get_pg_pool, PgPool},
+    domain::{CapacityPolicy, DailyCapacity},
     filters::api_filters,
+    storage::Storage,
 };
-use std::{env, net::IpAddr};
+use std::{env, net::IpAddr, path::PathBuf};
 use warp::Filter;
 
+mod attachment;
+mod board;
 mod card;
+mod checklist;
+mod comment;
+mod custom_field;
 mod database;
+mod dependency;
 mod domain;
 mod errors;
 mod filters;
+mod hierarchy;
+mod history;
+mod label;
+mod placeholder;
+mod recurrence;
+mod rrule;
+// generated by diesel print_schema, which imports the custom types in every table
+#[allow(unused_imports)]
 mod schema;
+mod storage;
+mod template;
+mod time_entry;
+mod trash;
+mod user;
+mod validation;
+mod workflow;
 
 pub struct ConfigMapReponse {
     pub ip: IpAddr,
     pub port: u16,
     pub database_url: String,
+    pub trash_retention_days: i64,
+    pub attachments_dir: PathBuf,
+    pub attachment_max_size: u64,
+    pub recurrence_horizon_days: i64,
+    pub daily_capacity: DailyCapacity,
+    pub admin_token: Option<String>,
+}
+
+/// Longest span a number of days of the configuration can set, ten years
+const CONFIG_DAYS_MAX: i64 = 3650;
+
+/// Reads a number of days from the environment, refusing to start outside 0..=CONFIG_DAYS_MAX.
+/// Swagger config:
+///   - N/A (helper function)
+fn env_days(name: &str, default: i64) -> i64 {
+    let days = env::var(name)
+        .map(|days| {
+            days.parse::<i64>()
+                .unwrap_or_else(|_| panic!("{} is not a number", name))
+        })
+        .unwrap_or(default);
+
+    if !(0..=CONFIG_DAYS_MAX).contains(&days) {
+        panic!("{} must be between 0 and {} days", name, CONFIG_DAYS_MAX);
+    }
+    days
+}
+
+/// Longest day a number of hours of the configuration can set
+const CONFIG_HOURS_MAX: i32 = 24;
+
+/// Reads a number of hours from the environment, refusing to start outside 1..=CONFIG_HOURS_MAX.
+/// Swagger config:
+///   - N/A (helper function)
+fn env_hours(name: &str, default: i32) -> i32 {
+    let hours = env::var(name)
+        .map(|hours| {
+            hours
+                .parse::<i32>()
+                .unwrap_or_else(|_| panic!("{} is not a number", name))
+        })
+        .unwrap_or(default);
+
+    if !(1..=CONFIG_HOURS_MAX).contains(&hours) {
+        panic!("{} must be between 1 and {} hours", name, CONFIG_HOURS_MAX);
+    }
+    hours
 }
 
 /// Retrieves the application configuration.
@@ -39,10 +111,47 @@ pub fn get_app_config() -> ConfigMapReponse {
         .parse::<u16>()
         .unwrap();
 
+    let trash_retention_days = env_days("TRASH_RETENTION_DAYS", 30);
+
+    let attachments_dir = env::var("ATTACHMENTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("attachments"));
+
+    let attachment_max_size = env::var("ATTACHMENT_MAX_SIZE")
+        .map(|size| {
+            size.parse::<u64>()
+                .expect("ATTACHMENT_MAX_SIZE is not a number")
+        })
+        .unwrap_or(10 * 1024 * 1024);
+
+    let recurrence_horizon_days = env_days("RECURRENCE_HORIZON_DAYS", 14);
+
+    let daily_capacity = DailyCapacity {
+        hours: env_hours("DAILY_CAPACITY", 8),
+        policy: env::var("CAPACITY_POLICY")
+            .map(|policy| {
+                policy
+                    .parse::<CapacityPolicy>()
+                    .expect("CAPACITY_POLICY is neither warn nor reject")
+            })
+            .unwrap_or(CapacityPolicy::Warn),
+    };
+
+    // Without one, WIP limit overrides are refused
+    let admin_token = env::var("ADMIN_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty());
+
     ConfigMapReponse {
         ip: app_ip,
         port: app_port,
-        database_url: database_url,
+        database_url,
+        trash_retention_days,
+        attachments_dir,
+        attachment_max_size,
+        recurrence_horizon_days,
+        daily_capacity,
+        admin_token,
     }
 }
 
@@ -58,6 +167,7 @@ async fn main() {
     pretty_env_logger::init();
     let config: ConfigMapReponse = get_app_config();
     let db_pool: PgPool = get_pg_pool(&config.database_url);
+    let storage = Storage::new(config.attachments_dir, config.attachment_max_size);
 
     let cors = warp::cors()
         .allow_any_origin()
@@ -67,15 +177,36 @@ async fn main() {
             "Access-Control-Request-Method",
             "Authorization",
             "Content-Type",
+            "If-Match",
             "Origin",
+            "Range",
             "Referer",
+            "X-Author",
+            "X-Wip-Override",
             "Sec-Fetch-Mode",
             "User-Agent",
         ])
-        .allow_methods(vec!["OPTIONS", "POST", "GET", "PUT", "PATCH", "DELETE"]);
+        .allow_methods(vec!["OPTIONS", "POST", "GET", "PUT", "PATCH", "DELETE"])
+        .expose_headers(vec![
+            "Accept-Ranges",
+            "Content-Disposition",
+            "Content-Range",
+            "ETag",
+            "X-Capacity-Warning",
+        ]);
+
+    tokio::spawn(trash::purge_expired_cards(
+        db_pool.clone(),
+        storage.clone(),
+        config.trash_retention_days,
+    ));
+    tokio::spawn(recurrence::materialise_recurrences(
+        db_pool.clone(),
+        config.recurrence_horizon_days,
+    ));
 
     // Get routes definition
-    let routes = api_filters(db_pool)
+    let routes = api_filters(db_pool, storage, config.daily_capacity, config.admin_token)
         .recover(errors::handle_rejection)
         .with(cors);
 
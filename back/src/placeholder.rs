@@ -0,0 +1,115 @@
+use chrono::{Duration, NaiveDate};
+use serde_json::Value;
+
+/// Value of one placeholder: `today` or `date`, optionally shifted by a number of days
+/// such as `date+3` or `today-1`
+fn resolve_placeholder(name: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let name: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+    let (base, offset) = match name.find(['+', '-']) {
+        Some(index) => name.split_at(index),
+        None => (name.as_str(), ""),
+    };
+
+    if base != "today" && base != "date" {
+        return Err(format!(
+            "`{{{{{}}}}}` is not a placeholder, expected today or date+N",
+            name
+        ));
+    }
+    if offset.is_empty() {
+        return Ok(today);
+    }
+
+    offset
+        .strip_prefix('+')
+        .unwrap_or(offset)
+        .parse::<i32>()
+        .ok()
+        .and_then(|days| today.checked_add_signed(Duration::days(i64::from(days))))
+        .ok_or_else(|| format!("`{{{{{}}}}}` does not shift by a number of days", name))
+}
+
+/// Replace the placeholders of a string by the dates they stand for, as YYYY-MM-DD
+pub fn resolve_str(text: &str, today: NaiveDate) -> Result<String, String> {
+    let mut resolved = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| format!("`{}` has an unclosed placeholder", text))?;
+
+        resolved.push_str(&rest[..start]);
+        let date = resolve_placeholder(&rest[start + 2..start + end], today)?;
+        resolved.push_str(&date.format("%Y-%m-%d").to_string());
+        rest = &rest[start + end + 2..];
+    }
+    resolved.push_str(rest);
+
+    Ok(resolved)
+}
+
+/// Resolve the placeholders of every string of a JSON value, keys are left as they are
+/// # Arguments
+/// * `value` - JSON value holding placeholders such as `{{today}}` or `{{date+3}}`
+/// * `today` - Date `{{today}}` and `{{date}}` stand for
+///
+pub fn resolve(value: &Value, today: NaiveDate) -> Result<Value, String> {
+    match value {
+        Value::String(text) => resolve_str(text, today).map(Value::String),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| resolve(value, today))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| resolve(value, today).map(|value| (key.clone(), value)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        value => Ok(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 10, 30).unwrap()
+    }
+
+    #[test]
+    fn resolves_shifted_placeholders() {
+        assert_eq!(
+            resolve_str(
+                "Review {{today}} to {{ date+3 }}, since {{today-31}}",
+                today()
+            )
+            .unwrap(),
+            "Review 2023-10-30 to 2023-11-02, since 2023-09-29"
+        );
+        assert_eq!(
+            resolve_str("no placeholder", today()).unwrap(),
+            "no placeholder"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_unclosed_placeholders() {
+        assert!(resolve_str("{{tomorrow}}", today()).is_err());
+        assert!(resolve_str("{{date+x}}", today()).is_err());
+        assert!(resolve_str("due {{today", today()).is_err());
+    }
+
+    #[test]
+    fn resolves_nested_values_and_keeps_keys() {
+        let value = json!({"title": "{{today}}", "tags": ["{{date+1}}", 3], "{{today}}": null});
+
+        assert_eq!(
+            resolve(&value, today()).unwrap(),
+            json!({"title": "2023-10-30", "tags": ["2023-10-31", 3], "{{today}}": null})
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    card::respond,
+    database::{DBAccessManager, PgPool},
+    domain::{Recurrence, RecurrenceData},
+    errors::{AppError, ErrorType},
+    validation::Validate,
+};
+
+/// How often the occurrences of the recurring cards are generated
+const MATERIALISE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Get the recurrence of a card.
+/// Swagger config:
+///   - Operation ID: get_recurrence
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/recurrence
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON representation of the recurrence
+///   - Error 404: the card does not repeat
+pub async fn get_recurrence(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_recurrence(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Make a card repeat, the card becomes the template of its occurrences.
+/// Swagger config:
+///   - Operation ID: set_recurrence
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/recurrence
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: RecurrenceData, an RRULE such as "FREQ=WEEKLY;BYDAY=MO"
+///   - Response: JSON representation of the recurrence, with its normalized rule
+///   - Error 422: the rule is not supported
+pub async fn set_recurrence(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: RecurrenceData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Recurrence>(Err(err), status);
+    }
+
+    match _db_manager.set_recurrence(_board_id, _id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Stop a card from repeating, its occurrences are kept.
+/// Swagger config:
+///   - Operation ID: delete_recurrence
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/recurrence
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted recurrences
+pub async fn delete_recurrence(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_recurrence(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Background task generating the upcoming occurrences of the recurring cards
+/// Runs once at startup then every hour, a run only creates the occurrences still missing
+/// # Arguments
+/// * `pool` - The database connection pool
+/// * `horizon_days` - How many days ahead occurrences are generated
+///
+pub async fn materialise_recurrences(pool: PgPool, horizon_days: i64) {
+    let mut interval = tokio::time::interval(MATERIALISE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let pool = pool.clone();
+        let created = tokio::task::spawn_blocking(move || {
+            let connection = pool.get().map_err(|err| {
+                AppError::new(
+                    format!("Error getting connection from pool: {}", err).as_str(),
+                    ErrorType::Internal,
+                )
+            })?;
+
+            let today = Utc::now().date_naive();
+            DBAccessManager::new(connection)
+                .materialise_recurrences(today, today + Duration::days(horizon_days))
+        })
+        .await;
+
+        match created {
+            Ok(Ok(0)) => {}
+            Ok(Ok(created)) => log::info!("Generated {} recurring card(s)", created),
+            Ok(Err(err)) => log::error!("Error while generating recurring cards: {}", err),
+            Err(err) => log::error!("Recurring cards task failed: {}", err),
+        }
+    }
+}
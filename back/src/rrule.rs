@@ -0,0 +1,353 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::{fmt, str::FromStr};
+
+/// Frequencies of the supported RFC 5545 subset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Recurrence rule, the subset of RFC 5545 `RRULE` cards can repeat on:
+/// `FREQ` (DAILY, WEEKLY or MONTHLY), `INTERVAL`, `BYDAY` (plain weekdays), `UNTIL` or `COUNT`
+/// such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=10`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Sorted from Monday, empty when the rule does not filter on weekdays
+    pub by_day: Vec<Weekday>,
+    pub until: Option<NaiveDate>,
+    pub count: Option<u32>,
+}
+
+fn parse_weekday(day: &str) -> Result<Weekday, String> {
+    match day {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        day => Err(format!("`{}` is not a weekday, expected MO to SU", day)),
+    }
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// `UNTIL` is a date, or a date-time of which only the date is kept
+fn parse_until(until: &str) -> Result<NaiveDate, String> {
+    let day = until.split('T').next().unwrap_or_default();
+
+    NaiveDate::parse_from_str(day, "%Y%m%d")
+        .map_err(|_| format!("UNTIL `{}` is not a YYYYMMDD date", until))
+}
+
+/// First day of the month `months` after the one of `date`
+fn add_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    let month0 = date.month0() + months;
+    let year = date.year() + (month0 / 12) as i32;
+
+    NaiveDate::from_ymd_opt(year, month0 % 12 + 1, 1)
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = Vec::new();
+        let mut until = None;
+        let mut count = None;
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("`{}` is not a NAME=VALUE pair", part))?;
+
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        value => {
+                            return Err(format!(
+                                "FREQ `{}` is not supported, expected DAILY, WEEKLY or MONTHLY",
+                                value
+                            ))
+                        }
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| format!("INTERVAL `{}` is not a positive number", value))?
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let day = parse_weekday(&day.trim().to_ascii_uppercase())?;
+                        if !by_day.contains(&day) {
+                            by_day.push(day);
+                        }
+                    }
+                    by_day.sort_by_key(|day| day.num_days_from_monday());
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or_else(|| format!("COUNT `{}` is not a positive number", value))?,
+                    )
+                }
+                name => return Err(format!("`{}` is not supported", name)),
+            }
+        }
+
+        if until.is_some() && count.is_some() {
+            return Err("UNTIL and COUNT cannot be combined".to_string());
+        }
+
+        Ok(RecurrenceRule {
+            frequency: frequency.ok_or("FREQ is required")?,
+            interval,
+            by_day,
+            until,
+            count,
+        })
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RecurrenceRule {
+    /// First day of a period of the rule, whether it holds an occurrence or not
+    /// # Arguments
+    /// * `start` - First occurrence, the `DTSTART` of the rule
+    /// * `period` - Index of the period, counted in `INTERVAL`s from the one of `start`
+    ///
+    fn period_start(&self, start: NaiveDate, period: u32) -> Option<NaiveDate> {
+        let step = i64::from(period) * i64::from(self.interval);
+
+        match self.frequency {
+            Frequency::Daily => start.checked_add_signed(Duration::days(step)),
+            Frequency::Weekly => {
+                let monday =
+                    start - Duration::days(i64::from(start.weekday().num_days_from_monday()));
+                monday.checked_add_signed(Duration::weeks(step))
+            }
+            Frequency::Monthly => u32::try_from(step)
+                .ok()
+                .and_then(|months| add_months(start, months)),
+        }
+    }
+
+    /// Dates of the period starting at `first`, in order
+    fn period_dates(&self, start: NaiveDate, first: NaiveDate) -> Vec<NaiveDate> {
+        match self.frequency {
+            Frequency::Daily if self.by_day.is_empty() => vec![first],
+            Frequency::Daily => Some(first)
+                .filter(|day| self.by_day.contains(&day.weekday()))
+                .into_iter()
+                .collect(),
+            Frequency::Weekly if self.by_day.is_empty() => {
+                vec![first + Duration::days(i64::from(start.weekday().num_days_from_monday()))]
+            }
+            Frequency::Weekly => self
+                .by_day
+                .iter()
+                .map(|day| first + Duration::days(i64::from(day.num_days_from_monday())))
+                .collect(),
+            // Months too short for the day of `start` are skipped, as RFC 5545 does
+            Frequency::Monthly if self.by_day.is_empty() => {
+                first.with_day(start.day()).into_iter().collect()
+            }
+            Frequency::Monthly => first
+                .iter_days()
+                .take_while(|day| day.month() == first.month())
+                .filter(|day| self.by_day.contains(&day.weekday()))
+                .collect(),
+        }
+    }
+
+    /// Occurrences of the rule up to a date, `start` being the first one
+    /// # Arguments
+    /// * `start` - First occurrence, the `DTSTART` of the rule
+    /// * `end` - Last date returned, inclusive
+    ///
+    pub fn occurrences(&self, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+        let end = self.until.map_or(end, |until| until.min(end));
+        let mut dates = Vec::new();
+        if start > end {
+            return dates;
+        }
+        dates.push(start);
+
+        let mut period = 0;
+        while let Some(first) = self
+            .period_start(start, period)
+            .filter(|first| *first <= end)
+        {
+            for day in self.period_dates(start, first) {
+                if day <= start {
+                    continue;
+                }
+                if day > end
+                    || self
+                        .count
+                        .is_some_and(|count| dates.len() >= count as usize)
+                {
+                    return dates;
+                }
+                dates.push(day);
+            }
+            period += 1;
+        }
+
+        dates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn rule(rule: &str) -> RecurrenceRule {
+        rule.parse().unwrap()
+    }
+
+    #[test]
+    fn weekly_by_day_from_mid_week_start() {
+        let dates =
+            rule("FREQ=WEEKLY;BYDAY=MO,TH").occurrences(date(2023, 9, 13), date(2023, 9, 26));
+
+        assert_eq!(
+            dates,
+            vec![
+                date(2023, 9, 13),
+                date(2023, 9, 14),
+                date(2023, 9, 18),
+                date(2023, 9, 21),
+                date(2023, 9, 25),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_interval_skips_weeks() {
+        let dates = rule("FREQ=WEEKLY;INTERVAL=2").occurrences(date(2023, 9, 6), date(2023, 10, 4));
+
+        assert_eq!(
+            dates,
+            vec![date(2023, 9, 6), date(2023, 9, 20), date(2023, 10, 4)]
+        );
+    }
+
+    #[test]
+    fn monthly_on_the_31st_skips_short_months() {
+        let dates = rule("FREQ=MONTHLY").occurrences(date(2023, 1, 31), date(2023, 8, 31));
+
+        assert_eq!(
+            dates,
+            vec![
+                date(2023, 1, 31),
+                date(2023, 3, 31),
+                date(2023, 5, 31),
+                date(2023, 7, 31),
+                date(2023, 8, 31)
+            ]
+        );
+    }
+
+    #[test]
+    fn count_includes_the_first_occurrence() {
+        let dates = rule("FREQ=DAILY;COUNT=3").occurrences(date(2023, 9, 1), date(2023, 12, 31));
+
+        assert_eq!(
+            dates,
+            vec![date(2023, 9, 1), date(2023, 9, 2), date(2023, 9, 3)]
+        );
+    }
+
+    #[test]
+    fn until_is_inclusive_and_bounds_the_horizon() {
+        let until = rule("FREQ=DAILY;UNTIL=20230903T235959Z");
+
+        assert_eq!(
+            until.occurrences(date(2023, 9, 1), date(2023, 12, 31)),
+            vec![date(2023, 9, 1), date(2023, 9, 2), date(2023, 9, 3)]
+        );
+        assert_eq!(
+            until.occurrences(date(2023, 9, 1), date(2023, 9, 2)),
+            vec![date(2023, 9, 1), date(2023, 9, 2)]
+        );
+        assert!(until
+            .occurrences(date(2023, 9, 4), date(2023, 12, 31))
+            .is_empty());
+    }
+
+    #[test]
+    fn count_and_until_cannot_be_combined() {
+        assert!("FREQ=DAILY;COUNT=3;UNTIL=20230903"
+            .parse::<RecurrenceRule>()
+            .is_err());
+    }
+
+    #[test]
+    fn display_normalizes_the_rule() {
+        assert_eq!(
+            rule("RRULE:freq=weekly;byday=th,mo,TH;interval=1;count=4").to_string(),
+            "FREQ=WEEKLY;BYDAY=MO,TH;COUNT=4"
+        );
+    }
+}
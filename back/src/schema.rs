@@ -1,11 +1,280 @@
 table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    attachments (id) {
+        id -> Int8,
+        card_id -> Int8,
+        filename -> Varchar,
+        size -> Int8,
+        mime -> Varchar,
+        sha256 -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    boards (id) {
+        id -> Int8,
+        name -> Varchar,
+        description -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        daily_capacity -> Nullable<Int4>,
+        capacity_policy -> Nullable<Capacity_policy>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    card_assignees (card_id, user_id) {
+        card_id -> Int8,
+        user_id -> Int8,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    card_dependencies (blocker_id, blocked_id) {
+        blocker_id -> Int8,
+        blocked_id -> Int8,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    card_labels (card_id, label_id) {
+        card_id -> Int8,
+        label_id -> Int8,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    card_revisions (id) {
+        id -> Int8,
+        card_id -> Int8,
+        version -> Int4,
+        action -> Varchar,
+        author -> Nullable<Varchar>,
+        snapshot -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    card_templates (id) {
+        id -> Int8,
+        board_id -> Int8,
+        name -> Varchar,
+        card -> Jsonb,
+        checklist -> Array<Text>,
+        label_ids -> Array<Int8>,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    checklist_items (id) {
+        id -> Int8,
+        card_id -> Int8,
+        text -> Varchar,
+        done -> Bool,
+        position -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    comments (id) {
+        id -> Int8,
+        card_id -> Int8,
+        author -> Nullable<Varchar>,
+        body -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    custom_fields (id) {
+        id -> Int8,
+        board_id -> Int8,
+        name -> Varchar,
+        field_type -> Custom_field_type,
+        options -> Array<Text>,
+        required -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
     data (id) {
         id -> Int8,
         title -> Varchar,
         description -> Varchar,
         date -> Date,
-        priority -> Varchar,
+        priority -> Card_priority,
         duration -> Int4,
-        status -> Varchar,
+        status -> Card_status,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        version -> Int4,
+        deleted_at -> Nullable<Timestamptz>,
+        board_id -> Int8,
+        rank -> Varchar,
+        recurrence_id -> Nullable<Int8>,
+        due_at -> Nullable<Timestamptz>,
+        time_zone -> Nullable<Varchar>,
+        parent_id -> Nullable<Int8>,
+        custom -> Jsonb,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    labels (id) {
+        id -> Int8,
+        board_id -> Int8,
+        name -> Varchar,
+        color -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    recurrences (id) {
+        id -> Int8,
+        card_id -> Int8,
+        rule -> Varchar,
+        generated_until -> Nullable<Date>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    time_entries (id) {
+        id -> Int8,
+        card_id -> Int8,
+        user_id -> Int8,
+        started_at -> Timestamptz,
+        ended_at -> Nullable<Timestamptz>,
+        note -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    users (id) {
+        id -> Int8,
+        name -> Varchar,
+        created_at -> Timestamptz,
+        daily_capacity -> Nullable<Int4>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    workflow_columns (id) {
+        id -> Int8,
+        board_id -> Int8,
+        status -> Card_status,
+        name -> Varchar,
+        color -> Varchar,
+        position -> Int4,
+        wip_limit -> Nullable<Int4>,
+        assignee_wip_limit -> Nullable<Int4>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::domain::sql_types::*;
+
+    workflow_transitions (board_id, from_status, to_status) {
+        board_id -> Int8,
+        from_status -> Card_status,
+        to_status -> Card_status,
     }
 }
+
+joinable!(attachments -> data (card_id));
+joinable!(card_assignees -> data (card_id));
+joinable!(card_assignees -> users (user_id));
+joinable!(card_labels -> data (card_id));
+joinable!(card_labels -> labels (label_id));
+joinable!(card_revisions -> data (card_id));
+joinable!(card_templates -> boards (board_id));
+joinable!(checklist_items -> data (card_id));
+joinable!(comments -> data (card_id));
+joinable!(custom_fields -> boards (board_id));
+joinable!(data -> boards (board_id));
+joinable!(labels -> boards (board_id));
+joinable!(recurrences -> data (card_id));
+joinable!(time_entries -> data (card_id));
+joinable!(time_entries -> users (user_id));
+joinable!(workflow_columns -> boards (board_id));
+joinable!(workflow_transitions -> boards (board_id));
+
+allow_tables_to_appear_in_same_query!(
+    attachments,
+    boards,
+    card_assignees,
+    card_dependencies,
+    card_labels,
+    card_revisions,
+    card_templates,
+    checklist_items,
+    comments,
+    custom_fields,
+    data,
+    labels,
+    recurrences,
+    time_entries,
+    users,
+    workflow_columns,
+    workflow_transitions,
+);
@@ -0,0 +1,84 @@
+use std::{
+    convert::Infallible,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use warp::Filter;
+
+/// Prefix of the files an upload is written to before it is committed
+const UPLOAD_PREFIX: &str = ".upload-";
+
+/// Makes the names of concurrent uploads unique
+static UPLOAD_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Directory holding the attachment files, each one named after its attachment id
+#[derive(Debug, Clone)]
+pub struct Storage {
+    dir: PathBuf,
+    /// Largest upload request accepted, in bytes
+    pub max_size: u64,
+}
+
+impl Storage {
+    /// Open the storage directory, creating it when needed
+    /// Uploads interrupted by a previous run are removed
+    /// # Arguments
+    /// * `dir` - Directory the files are stored in
+    /// * `max_size` - Largest upload request accepted, in bytes
+    ///
+    pub fn new(dir: PathBuf, max_size: u64) -> Storage {
+        std::fs::create_dir_all(&dir).expect("attachment storage directory could not be created");
+
+        let entries =
+            std::fs::read_dir(&dir).expect("attachment storage directory is not readable");
+        for entry in entries.flatten() {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(UPLOAD_PREFIX)
+            {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+
+        Storage { dir, max_size }
+    }
+
+    /// Path of the file of an attachment
+    pub fn path(&self, attachment_id: i64) -> PathBuf {
+        self.dir.join(attachment_id.to_string())
+    }
+
+    /// Path an upload is written to before its attachment id is known
+    pub fn upload_path(&self) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}-{}",
+            UPLOAD_PREFIX,
+            std::process::id(),
+            UPLOAD_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ))
+    }
+
+    /// Remove the files of deleted attachments, files already gone are ignored
+    pub async fn remove(&self, attachment_ids: &[i64]) {
+        for id in attachment_ids {
+            match tokio::fs::remove_file(self.path(*id)).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => log::error!("Error while removing attachment {}: {}", id, err),
+            }
+        }
+    }
+}
+
+/// Attachment storage for warp routes
+/// # Arguments
+/// * `storage` - The attachment storage, type Storage
+/// # Returns
+/// * `Filter` - The storage filter, type Filter
+///
+pub fn with_storage(
+    storage: Storage,
+) -> impl Filter<Extract = (Storage,), Error = Infallible> + Clone {
+    warp::any().map(move || storage.clone())
+}
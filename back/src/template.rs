@@ -0,0 +1,188 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+
+use crate::{
+    card::{capacity_warnings, respond, respond_card, respond_checked},
+    database::DBAccessManager,
+    domain::{
+        instantiate_template, CapacityQuery, CardTemplate, CardTemplateData, CardView,
+        DailyCapacity,
+    },
+    errors::{AppError, ErrorType},
+    validation::Validate,
+};
+
+/// Create a new card template.
+/// Swagger config:
+///   - Operation ID: create_template
+///   - HTTP Method: POST
+///   - Path: /templates
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Request Body: CardTemplateData, a CardData whose strings may hold {{today}} or {{date+N}}
+///   - Response: JSON representation of the created template
+///   - Error 400: one of the labels does not belong to the board
+///   - Error 409: the board already has a template with this name
+///   - Error 422: list of the fields that failed validation
+pub async fn create_template(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: CardTemplateData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<CardTemplate>(Err(err), status);
+    }
+
+    match _db_manager.create_template(_board_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get all card templates of a board.
+/// Swagger config:
+///   - Operation ID: get_templates
+///   - HTTP Method: GET
+///   - Path: /templates
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Response: JSON representation of the templates, sorted by name
+pub async fn get_templates(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_templates(_board_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get a card template.
+/// Swagger config:
+///   - Operation ID: get_template
+///   - HTTP Method: GET
+///   - Path: /templates/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON representation of the template
+pub async fn get_template(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_template(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit an existing card template.
+/// Swagger config:
+///   - Operation ID: edit_template
+///   - HTTP Method: PUT
+///   - Path: /templates/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: CardTemplateData
+///   - Response: JSON representation of the edited template
+///   - Error 400: one of the labels does not belong to the board
+///   - Error 409: the board already has a template with this name
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_template(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: CardTemplateData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<CardTemplate>(Err(err), status);
+    }
+
+    match _db_manager.edit_template(_board_id, _id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete a card template, the cards created from it are kept.
+/// Swagger config:
+///   - Operation ID: delete_template
+///   - HTTP Method: DELETE
+///   - Path: /templates/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted templates
+pub async fn delete_template(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_template(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Create a card from a template, its placeholders resolved against today.
+/// Swagger config:
+///   - Operation ID: create_card_from_template
+///   - HTTP Method: POST
+///   - Path: /cards/from-template/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer, template of the same board)
+///   - Query Parameter: tz (IANA time zone today is read in, optional, UTC by default)
+///   - Header: X-Time-Zone (same as tz, the query parameter wins)
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
+///   - Response: JSON representation of the created card with its labels and checklist, with its ETag
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the column, or an assignee in it, is at its WIP limit (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day goes over a daily capacity under the reject policy
+///   - Error 422: the resolved card failed validation
+pub async fn create_card_from_template(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _time_zone: Tz,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    let today = Utc::now().with_timezone(&_time_zone).date_naive();
+
+    let created = _db_manager
+        .get_template(_board_id, _id)
+        .and_then(|template| {
+            let (card, checklist) =
+                instantiate_template(&template.card, &template.checklist, today)
+                    .map_err(|err| AppError::new(err.as_str(), ErrorType::MissingRequiredField))?;
+            card.validate()?;
+            _db_manager.create_card_from_template(_board_id, &template, card, checklist)
+        });
+
+    match created {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch.card);
+            respond_checked(_branch, warnings, warp::http::StatusCode::CREATED)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
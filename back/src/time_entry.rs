@@ -0,0 +1,90 @@
+use crate::{
+    card::respond,
+    database::DBAccessManager,
+    domain::{TimeEntry, TimerData},
+    validation::Validate,
+};
+
+/// Get the time entries of a card.
+/// Swagger config:
+///   - Operation ID: get_time_entries
+///   - HTTP Method: GET
+///   - Path: /cards/{id}/time_entries
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: JSON list of the time entries, the oldest first
+pub async fn get_time_entries(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_time_entries(_board_id, _id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Start a timer on a card.
+/// Swagger config:
+///   - Operation ID: start_timer
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/timer/start
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: TimerData, the user tracking the time and an optional note
+///   - Response: JSON representation of the running time entry
+///   - Error 409: the user already has a running timer
+///   - Error 422: list of the fields that failed validation
+pub async fn start_timer(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _timer: TimerData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _timer.validate() {
+        let status = err.to_status_code();
+        return respond::<TimeEntry>(Err(err), status);
+    }
+
+    match _db_manager.start_timer(_board_id, _id, _timer) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Stop the running timer of a user on a card.
+/// Swagger config:
+///   - Operation ID: stop_timer
+///   - HTTP Method: POST
+///   - Path: /cards/{id}/timer/stop
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Request Body: TimerData, the note replaces the one given at start when present
+///   - Response: JSON representation of the stopped time entry
+///   - Error 404: the user has no running timer on the card
+///   - Error 422: list of the fields that failed validation
+pub async fn stop_timer(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _timer: TimerData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _timer.validate() {
+        let status = err.to_status_code();
+        return respond::<TimeEntry>(Err(err), status);
+    }
+
+    match _db_manager.stop_timer(_board_id, _id, _timer) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::{Duration, Utc};
+
+use crate::{
+    card::respond,
+    database::{DBAccessManager, PgPool},
+    errors::{AppError, ErrorType},
+    storage::Storage,
+};
+
+/// How often the trash is scanned for expired cards
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Get all cards in the trash.
+/// Swagger config:
+///   - Operation ID: get_trash
+///   - HTTP Method: GET
+///   - Path: /trash
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Response: JSON representation of the deleted cards, most recent first
+pub async fn get_trash(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_trash(_board_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(_) => respond(
+            Err(AppError::new("Internal server error", ErrorType::Internal)),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    }
+}
+
+/// Permanently delete a card from the trash.
+/// Swagger config:
+///   - Operation ID: purge_card
+///   - HTTP Method: DELETE
+///   - Path: /trash/{id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Response: number of purged cards, their attachment files are removed
+pub async fn purge_card(
+    _board_id: i64,
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _storage: Storage,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.purge_card(_board_id, _id) {
+        Ok(_branch) => {
+            _storage.remove(&_branch.attachment_ids).await;
+            respond(Ok(_branch.cards), warp::http::StatusCode::ACCEPTED)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Periodically purge the cards that stayed in the trash longer than the retention.
+/// Swagger config:
+///   - N/A (background task)
+pub async fn purge_expired_cards(pool: PgPool, storage: Storage, retention_days: i64) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let pool = pool.clone();
+        let purged = tokio::task::spawn_blocking(move || {
+            let connection = pool.get().map_err(|err| {
+                AppError::new(
+                    format!("Error getting connection from pool: {}", err).as_str(),
+                    ErrorType::Internal,
+                )
+            })?;
+
+            DBAccessManager::new(connection)
+                .purge_trash(Utc::now() - Duration::days(retention_days))
+        })
+        .await;
+
+        match purged {
+            Ok(Ok(purged)) if purged.cards == 0 => {}
+            Ok(Ok(purged)) => {
+                storage.remove(&purged.attachment_ids).await;
+                log::info!("Purged {} card(s) from the trash", purged.cards)
+            }
+            Ok(Err(err)) => log::error!("Error while purging the trash: {}", err),
+            Err(err) => log::error!("Trash purge task failed: {}", err),
+        }
+    }
+}
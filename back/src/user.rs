@@ -0,0 +1,179 @@
+use crate::{
+    card::{capacity_warnings, respond, respond_card, respond_checked},
+    database::DBAccessManager,
+    domain::{CapacityQuery, CardView, DailyCapacity, User, UserData},
+    validation::Validate,
+};
+
+/// Create a new user.
+/// Swagger config:
+///   - Operation ID: create_user
+///   - HTTP Method: POST
+///   - Path: /users
+///   - Request Body: UserData
+///   - Response: JSON representation of the created user
+///   - Error 409: a user with this name already exists
+///   - Error 422: list of the fields that failed validation
+pub async fn create_user(
+    _db_manager: DBAccessManager,
+    _new_data: UserData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<User>(Err(err), status);
+    }
+
+    match _db_manager.create_user(_new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::CREATED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get all users.
+/// Swagger config:
+///   - Operation ID: get_all_users
+///   - HTTP Method: GET
+///   - Path: /users
+///   - Response: JSON representation of all users, sorted by name
+pub async fn get_all_users(
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_all_users() {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Get a user by ID.
+/// Swagger config:
+///   - Operation ID: get_user_by_id
+///   - HTTP Method: GET
+///   - Path: /users/{id}
+///   - Path Parameter: id (integer)
+///   - Response: JSON representation of the user with the specified ID
+pub async fn get_user_by_id(
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_user(_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Edit an existing user.
+/// Swagger config:
+///   - Operation ID: edit_user
+///   - HTTP Method: PUT
+///   - Path: /users/{id}
+///   - Path Parameter: id (integer)
+///   - Request Body: UserData
+///   - Response: JSON representation of the edited user
+///   - Error 409: a user with this name already exists
+///   - Error 422: list of the fields that failed validation
+pub async fn edit_user(
+    _id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: UserData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<User>(Err(err), status);
+    }
+
+    match _db_manager.edit_user(_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Delete a user, unassigning them from every card.
+/// Swagger config:
+///   - Operation ID: delete_user
+///   - HTTP Method: DELETE
+///   - Path: /users/{id}
+///   - Path Parameter: id (integer)
+///   - Response: number of deleted users
+pub async fn delete_user(
+    _id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.delete_user(_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::ACCEPTED),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Assign a user to a card.
+/// Swagger config:
+///   - Operation ID: assign_user
+///   - HTTP Method: PUT
+///   - Path: /cards/{id}/assignees/{user_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: user_id (integer)
+///   - Query Parameter: allow_overbook (boolean, optional, goes over the daily capacities)
+///   - Response: JSON representation of the card with its assignees, with its ETag
+///   - Header: X-Capacity-Warning (percent-encoded, one per daily capacity in capacity_warnings)
+///   - Error 401: X-Wip-Override does not hold the admin token
+///   - Error 409: the user is at the WIP limit of the card column (X-Wip-Override with the admin token skips it)
+///   - Error 409: the day of the user goes over their daily capacity under the reject policy
+pub async fn assign_user(
+    _board_id: i64,
+    _id: i64,
+    _user_id: i64,
+    _db_manager: DBAccessManager,
+    _query: CapacityQuery,
+    _capacity: DailyCapacity,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    let _db_manager = _db_manager.with_capacity(_capacity, _query.allow_overbook);
+    match _db_manager.assign_user(_board_id, _id, _user_id) {
+        Ok(_branch) => {
+            let warnings = capacity_warnings(&_db_manager, &_branch.card);
+            respond_checked(_branch, warnings, warp::http::StatusCode::OK)
+        }
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
+
+/// Unassign a user from a card.
+/// Swagger config:
+///   - Operation ID: unassign_user
+///   - HTTP Method: DELETE
+///   - Path: /cards/{id}/assignees/{user_id}
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Path Parameter: id (integer)
+///   - Path Parameter: user_id (integer)
+///   - Response: JSON representation of the card with its assignees, with its ETag
+pub async fn unassign_user(
+    _board_id: i64,
+    _id: i64,
+    _user_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.unassign_user(_board_id, _id, _user_id) {
+        Ok(_branch) => respond_card(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond_card::<CardView>(Err(err), status)
+        }
+    }
+}
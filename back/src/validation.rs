@@ -0,0 +1,853 @@
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    domain::{
+        instantiate_template, local_date, BoardData, CardData, CardPatch, CardTemplateData,
+        ChecklistItemData, CommentData, CustomField, CustomFieldData, CustomFieldType,
+        DateRangeQuery, LabelData, PageQuery, RecurrenceData, TimerData, UserData, WorkflowData,
+        WorkloadQuery,
+    },
+    errors::{AppError, ErrorType, FieldError},
+    rrule::RecurrenceRule,
+};
+
+/// Maximum length of a card title, mirrors the client form
+pub const TITLE_MAX_LENGTH: usize = 50;
+/// Maximum length of a card description, mirrors the client form
+pub const DESCRIPTION_MAX_LENGTH: usize = 200;
+/// Maximum length of a board name
+pub const BOARD_NAME_MAX_LENGTH: usize = 50;
+/// Maximum length of a workflow column name
+pub const COLUMN_NAME_MAX_LENGTH: usize = 50;
+/// Maximum length of a label name
+pub const LABEL_NAME_MAX_LENGTH: usize = 30;
+/// Maximum length of a user name
+pub const USER_NAME_MAX_LENGTH: usize = 50;
+/// Maximum length of a card template name
+pub const TEMPLATE_NAME_MAX_LENGTH: usize = 50;
+/// Maximum length of a custom field name
+pub const CUSTOM_FIELD_NAME_MAX_LENGTH: usize = 30;
+/// Maximum length of a custom text or URL value
+pub const CUSTOM_VALUE_MAX_LENGTH: usize = 200;
+/// Maximum length of a checklist item
+pub const CHECKLIST_TEXT_MAX_LENGTH: usize = 100;
+/// Maximum length of a comment
+pub const COMMENT_BODY_MAX_LENGTH: usize = 2000;
+/// Maximum length of a time entry note
+pub const TIME_ENTRY_NOTE_MAX_LENGTH: usize = 200;
+/// Longest range of days a report covers
+pub const REPORT_DAYS_MAX: i64 = 366;
+/// Largest page the paginated lists return
+pub const PER_PAGE_MAX: i64 = 100;
+/// Last page number the paginated lists accept, keeps the row offset in range
+pub const PAGE_MAX: i64 = 1_000_000;
+/// Longest duration a single card can plan on one day, in hours
+pub const DURATION_MAX: i32 = 24;
+
+/// Payloads that must be checked before reaching the database
+pub trait Validate {
+    /// Collects every failing field instead of stopping at the first one
+    fn field_errors(&self) -> Vec<FieldError>;
+
+    /// Validate the payload
+    /// # Returns
+    /// * `AppError` - MissingRequiredField error listing every failing field
+    fn validate(&self) -> Result<(), AppError> {
+        check_fields(self.field_errors())
+    }
+}
+
+/// Turn the failing fields of a check into a MissingRequiredField error, none is a success
+pub fn check_fields(fields: Vec<FieldError>) -> Result<(), AppError> {
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    Err(AppError::with_fields(
+        format!("FIELD_ERROR: {} invalid field(s)", fields.len()).as_str(),
+        ErrorType::MissingRequiredField,
+        fields,
+    ))
+}
+
+/// Earliest date a card can be planned on
+fn min_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+/// Latest date a card can be planned on
+fn max_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2100, 12, 31).unwrap()
+}
+
+pub fn check_title(title: &str, errors: &mut Vec<FieldError>) {
+    if title.trim().is_empty() {
+        errors.push(FieldError::new("title", "must not be empty"));
+    } else if title.chars().count() > TITLE_MAX_LENGTH {
+        errors.push(FieldError::new(
+            "title",
+            format!("must be at most {} characters", TITLE_MAX_LENGTH).as_str(),
+        ));
+    }
+}
+
+pub fn check_description(description: &str, errors: &mut Vec<FieldError>) {
+    if description.chars().count() > DESCRIPTION_MAX_LENGTH {
+        errors.push(FieldError::new(
+            "description",
+            format!("must be at most {} characters", DESCRIPTION_MAX_LENGTH).as_str(),
+        ));
+    }
+}
+
+pub fn check_duration(duration: i32, errors: &mut Vec<FieldError>) {
+    if duration <= 0 || duration > DURATION_MAX {
+        errors.push(FieldError::new(
+            "duration",
+            format!("must be between 1 and {}", DURATION_MAX).as_str(),
+        ));
+    }
+}
+
+pub fn check_date(date: NaiveDate, errors: &mut Vec<FieldError>) {
+    check_date_field("date", date, errors);
+}
+
+/// Same bounds as the date of a card, for the dates of other fields such as a range
+fn check_date_field(field: &str, date: NaiveDate, errors: &mut Vec<FieldError>) {
+    if date < min_date() || date > max_date() {
+        errors.push(FieldError::new(
+            field,
+            format!("must be between {} and {}", min_date(), max_date()).as_str(),
+        ));
+    }
+}
+
+/// Time zones are IANA names, such as `Europe/Paris`
+fn check_time_zone(time_zone: &str, errors: &mut Vec<FieldError>) {
+    if time_zone.parse::<Tz>().is_err() {
+        errors.push(FieldError::new(
+            "time_zone",
+            "must be an IANA time zone, such as Europe/Paris",
+        ));
+    }
+}
+
+impl Validate for CardData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        check_title(&self.title, &mut errors);
+        check_description(&self.description, &mut errors);
+        match self.due_at {
+            Some(due_at) => check_date(local_date(due_at, self.time_zone.as_deref()), &mut errors),
+            None => check_date(self.date, &mut errors),
+        }
+        if let Some(time_zone) = &self.time_zone {
+            check_time_zone(time_zone, &mut errors);
+        }
+        check_duration(self.duration, &mut errors);
+
+        errors
+    }
+}
+
+impl Validate for CardPatch {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if let Some(title) = &self.title {
+            check_title(title, &mut errors);
+        }
+        if let Some(description) = &self.description {
+            check_description(description, &mut errors);
+        }
+        if let Some(date) = self.date {
+            check_date(date, &mut errors);
+        }
+        if let Some(duration) = self.duration {
+            check_duration(duration, &mut errors);
+        }
+        if let Some(Some(due_at)) = self.due_at {
+            let time_zone = self.time_zone.as_ref().and_then(|zone| zone.as_deref());
+            check_date(local_date(due_at, time_zone), &mut errors);
+        }
+        if let Some(Some(time_zone)) = &self.time_zone {
+            check_time_zone(time_zone, &mut errors);
+        }
+
+        errors
+    }
+}
+
+impl Validate for BoardData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "must not be empty"));
+        } else if self.name.chars().count() > BOARD_NAME_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "name",
+                format!("must be at most {} characters", BOARD_NAME_MAX_LENGTH).as_str(),
+            ));
+        }
+        check_description(&self.description, &mut errors);
+        if self.daily_capacity.is_some_and(|hours| hours < 1) {
+            errors.push(FieldError::new("daily_capacity", "must be at least 1"));
+        }
+
+        errors
+    }
+}
+
+/// Colors are sent as `#RRGGBB`
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl Validate for WorkflowData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.columns.is_empty() {
+            errors.push(FieldError::new(
+                "columns",
+                "must contain at least one column",
+            ));
+        }
+
+        for (index, column) in self.columns.iter().enumerate() {
+            if column.name.trim().is_empty() {
+                errors.push(FieldError::new(
+                    format!("columns[{}].name", index).as_str(),
+                    "must not be empty",
+                ));
+            } else if column.name.chars().count() > COLUMN_NAME_MAX_LENGTH {
+                errors.push(FieldError::new(
+                    format!("columns[{}].name", index).as_str(),
+                    format!("must be at most {} characters", COLUMN_NAME_MAX_LENGTH).as_str(),
+                ));
+            }
+            if !is_hex_color(&column.color) {
+                errors.push(FieldError::new(
+                    format!("columns[{}].color", index).as_str(),
+                    "must be a #RRGGBB color",
+                ));
+            }
+            for (field, limit) in [
+                ("wip_limit", column.wip_limit),
+                ("assignee_wip_limit", column.assignee_wip_limit),
+            ] {
+                if limit.is_some_and(|limit| limit < 1) {
+                    errors.push(FieldError::new(
+                        format!("columns[{}].{}", index, field).as_str(),
+                        "must be at least 1",
+                    ));
+                }
+            }
+            if self.columns[..index]
+                .iter()
+                .any(|previous| previous.status == column.status)
+            {
+                errors.push(FieldError::new(
+                    format!("columns[{}].status", index).as_str(),
+                    format!("`{}` already has a column", column.status).as_str(),
+                ));
+            }
+        }
+
+        let has_column = |status| self.columns.iter().any(|column| column.status == status);
+        for (index, transition) in self.transitions.iter().enumerate() {
+            if transition.from_status == transition.to_status {
+                errors.push(FieldError::new(
+                    format!("transitions[{}]", index).as_str(),
+                    "must go to another column",
+                ));
+            }
+            if !has_column(transition.from_status) || !has_column(transition.to_status) {
+                errors.push(FieldError::new(
+                    format!("transitions[{}]", index).as_str(),
+                    "must only reference statuses with a column",
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+impl Validate for LabelData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "must not be empty"));
+        } else if self.name.chars().count() > LABEL_NAME_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "name",
+                format!("must be at most {} characters", LABEL_NAME_MAX_LENGTH).as_str(),
+            ));
+        }
+        if !is_hex_color(&self.color) {
+            errors.push(FieldError::new("color", "must be a #RRGGBB color"));
+        }
+
+        errors
+    }
+}
+
+impl Validate for UserData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "must not be empty"));
+        } else if self.name.chars().count() > USER_NAME_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "name",
+                format!("must be at most {} characters", USER_NAME_MAX_LENGTH).as_str(),
+            ));
+        }
+        if self
+            .daily_capacity
+            .is_some_and(|hours| !(1..=DURATION_MAX).contains(&hours))
+        {
+            errors.push(FieldError::new(
+                "daily_capacity",
+                format!("must be between 1 and {}", DURATION_MAX).as_str(),
+            ));
+        }
+
+        errors
+    }
+}
+
+impl Validate for TimerData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self
+            .note
+            .as_ref()
+            .is_some_and(|note| note.chars().count() > TIME_ENTRY_NOTE_MAX_LENGTH)
+        {
+            errors.push(FieldError::new(
+                "note",
+                format!("must be at most {} characters", TIME_ENTRY_NOTE_MAX_LENGTH).as_str(),
+            ));
+        }
+
+        errors
+    }
+}
+
+impl Validate for DateRangeQuery {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        check_date_field("from", self.from, &mut errors);
+        check_date_field("to", self.to, &mut errors);
+
+        if self.to < self.from {
+            errors.push(FieldError::new("to", "must not be before from"));
+        } else if (self.to - self.from).num_days() >= REPORT_DAYS_MAX {
+            errors.push(FieldError::new(
+                "to",
+                format!("must be less than {} days after from", REPORT_DAYS_MAX).as_str(),
+            ));
+        }
+
+        errors
+    }
+}
+
+impl Validate for WorkloadQuery {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = DateRangeQuery {
+            from: self.from,
+            to: self.to,
+        }
+        .field_errors();
+
+        let periods = self
+            .group_by
+            .iter()
+            .filter(|group| group.is_period())
+            .count();
+        if periods > 1 || self.group_by.len() - periods > 1 {
+            errors.push(FieldError::new(
+                "group_by",
+                "takes at most one of day, week or month and one of status or priority",
+            ));
+        }
+
+        errors
+    }
+}
+
+impl Validate for CardTemplateData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "must not be empty"));
+        } else if self.name.chars().count() > TEMPLATE_NAME_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "name",
+                format!("must be at most {} characters", TEMPLATE_NAME_MAX_LENGTH).as_str(),
+            ));
+        }
+
+        // Checked as the card it would create today
+        let today = Utc::now().date_naive();
+        match instantiate_template(&self.card, &self.checklist, today) {
+            Ok((card, checklist)) => {
+                errors.extend(card.field_errors().into_iter().map(|error| FieldError {
+                    field: format!("card.{}", error.field),
+                    message: error.message,
+                }));
+                if checklist.iter().any(|text| {
+                    text.trim().is_empty() || text.chars().count() > CHECKLIST_TEXT_MAX_LENGTH
+                }) {
+                    errors.push(FieldError::new(
+                        "checklist",
+                        format!(
+                            "items must be between 1 and {} characters",
+                            CHECKLIST_TEXT_MAX_LENGTH
+                        )
+                        .as_str(),
+                    ));
+                }
+            }
+            Err(err) => errors.push(FieldError::new("card", err.as_str())),
+        }
+
+        errors
+    }
+}
+
+impl Validate for CustomFieldData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(FieldError::new("name", "must not be empty"));
+        } else if self.name.chars().count() > CUSTOM_FIELD_NAME_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "name",
+                format!(
+                    "must be at most {} characters",
+                    CUSTOM_FIELD_NAME_MAX_LENGTH
+                )
+                .as_str(),
+            ));
+        } else if self.name.contains([',', ':']) {
+            // Both separate the filters of the card list
+            errors.push(FieldError::new("name", "must not contain `,` or `:`"));
+        }
+
+        match self.field_type {
+            CustomFieldType::Select if self.options.is_empty() => {
+                errors.push(FieldError::new("options", "a select field needs options"));
+            }
+            CustomFieldType::Select => {
+                let mut seen = std::collections::HashSet::new();
+                if self
+                    .options
+                    .iter()
+                    .any(|option| option.trim().is_empty() || !seen.insert(option))
+                {
+                    errors.push(FieldError::new("options", "must be unique and not empty"));
+                }
+            }
+            _ if !self.options.is_empty() => {
+                errors.push(FieldError::new(
+                    "options",
+                    "only select fields have options",
+                ));
+            }
+            _ => {}
+        }
+
+        errors
+    }
+}
+
+/// Check the value of a custom field against its type
+/// # Arguments
+/// * `field` - Definition of the field
+/// * `value` - Value sent for the field, not null
+/// * `errors` - Receives an error named `custom.<field name>`
+///
+pub fn check_custom_value(
+    field: &CustomField,
+    value: &serde_json::Value,
+    errors: &mut Vec<FieldError>,
+) {
+    let text = value.as_str();
+    let error = match field.field_type {
+        CustomFieldType::Text => match text {
+            Some(text) if text.chars().count() <= CUSTOM_VALUE_MAX_LENGTH => None,
+            Some(_) => Some(format!(
+                "must be at most {} characters",
+                CUSTOM_VALUE_MAX_LENGTH
+            )),
+            None => Some("must be a string".to_string()),
+        },
+        CustomFieldType::Number if value.is_number() => None,
+        CustomFieldType::Number => Some("must be a number".to_string()),
+        CustomFieldType::Date => match text.map(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d"))
+        {
+            Some(Ok(_)) => None,
+            _ => Some("must be a YYYY-MM-DD date".to_string()),
+        },
+        CustomFieldType::Select => match text {
+            Some(text) if field.options.iter().any(|option| option == text) => None,
+            _ => Some(format!("must be one of {}", field.options.join(", "))),
+        },
+        CustomFieldType::Url => match text {
+            Some(url) if is_url(url) => None,
+            _ => Some("must be an absolute http or https URL".to_string()),
+        },
+    };
+
+    if let Some(message) = error {
+        errors.push(FieldError::new(
+            format!("custom.{}", field.name).as_str(),
+            message.as_str(),
+        ));
+    }
+}
+
+/// Absolute http or https URL with a host, without whitespace
+fn is_url(url: &str) -> bool {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+
+    match rest {
+        Some(rest) => {
+            url.chars().count() <= CUSTOM_VALUE_MAX_LENGTH
+                && !url.contains(char::is_whitespace)
+                && !rest
+                    .split(['/', '?', '#'])
+                    .next()
+                    .unwrap_or_default()
+                    .is_empty()
+        }
+        None => false,
+    }
+}
+
+impl Validate for RecurrenceData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        match self.rule.parse::<RecurrenceRule>() {
+            Ok(_) => Vec::new(),
+            Err(message) => vec![FieldError::new("rule", message.as_str())],
+        }
+    }
+}
+
+impl Validate for ChecklistItemData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.text.trim().is_empty() {
+            errors.push(FieldError::new("text", "must not be empty"));
+        } else if self.text.chars().count() > CHECKLIST_TEXT_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "text",
+                format!("must be at most {} characters", CHECKLIST_TEXT_MAX_LENGTH).as_str(),
+            ));
+        }
+        if matches!(self.position, Some(position) if position < 0) {
+            errors.push(FieldError::new("position", "must not be negative"));
+        }
+
+        errors
+    }
+}
+
+impl Validate for CommentData {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.body.trim().is_empty() {
+            errors.push(FieldError::new("body", "must not be empty"));
+        } else if self.body.chars().count() > COMMENT_BODY_MAX_LENGTH {
+            errors.push(FieldError::new(
+                "body",
+                format!("must be at most {} characters", COMMENT_BODY_MAX_LENGTH).as_str(),
+            ));
+        }
+
+        errors
+    }
+}
+
+impl Validate for PageQuery {
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.page() < 1 || self.page() > PAGE_MAX {
+            errors.push(FieldError::new(
+                "page",
+                format!("must be between 1 and {}", PAGE_MAX).as_str(),
+            ));
+        }
+        if self.per_page() < 1 || self.per_page() > PER_PAGE_MAX {
+            errors.push(FieldError::new(
+                "per_page",
+                format!("must be between 1 and {}", PER_PAGE_MAX).as_str(),
+            ));
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names of the fields a check reported
+    fn failing(check: impl FnOnce(&mut Vec<FieldError>)) -> Vec<String> {
+        let mut errors = Vec::new();
+        check(&mut errors);
+        errors.into_iter().map(|error| error.field).collect()
+    }
+
+    #[test]
+    fn title_must_be_set_and_short() {
+        assert!(failing(|errors| check_title("Write the report", errors)).is_empty());
+        assert_eq!(failing(|errors| check_title("   ", errors)), ["title"]);
+
+        let longest = "é".repeat(TITLE_MAX_LENGTH);
+        assert!(failing(|errors| check_title(&longest, errors)).is_empty());
+        let too_long = "é".repeat(TITLE_MAX_LENGTH + 1);
+        assert_eq!(failing(|errors| check_title(&too_long, errors)), ["title"]);
+    }
+
+    #[test]
+    fn description_may_be_empty_but_short() {
+        assert!(failing(|errors| check_description("", errors)).is_empty());
+
+        let longest = "a".repeat(DESCRIPTION_MAX_LENGTH);
+        assert!(failing(|errors| check_description(&longest, errors)).is_empty());
+        let too_long = "a".repeat(DESCRIPTION_MAX_LENGTH + 1);
+        assert_eq!(
+            failing(|errors| check_description(&too_long, errors)),
+            ["description"]
+        );
+    }
+
+    #[test]
+    fn duration_is_bounded() {
+        for duration in [1, DURATION_MAX] {
+            assert!(failing(|errors| check_duration(duration, errors)).is_empty());
+        }
+        for duration in [-1, 0, DURATION_MAX + 1] {
+            assert_eq!(
+                failing(|errors| check_duration(duration, errors)),
+                ["duration"]
+            );
+        }
+    }
+
+    #[test]
+    fn date_is_bounded() {
+        for date in [min_date(), max_date()] {
+            assert!(failing(|errors| check_date(date, errors)).is_empty());
+        }
+
+        let before = NaiveDate::from_ymd_opt(1969, 12, 31).unwrap();
+        let after = NaiveDate::from_ymd_opt(2101, 1, 1).unwrap();
+        for date in [before, after] {
+            assert_eq!(failing(|errors| check_date(date, errors)), ["date"]);
+        }
+    }
+
+    #[test]
+    fn every_failing_field_is_reported() {
+        let errors = failing(|errors| {
+            check_title("", errors);
+            check_description("", errors);
+            check_duration(0, errors);
+        });
+
+        assert_eq!(errors, ["title", "duration"]);
+    }
+
+    /// Names of the fields a payload fails on
+    fn invalid(payload: &impl Validate) -> Vec<String> {
+        payload
+            .field_errors()
+            .into_iter()
+            .map(|error| error.field)
+            .collect()
+    }
+
+    #[test]
+    fn pages_start_at_one_and_are_bounded() {
+        assert!(invalid(&PageQuery::default()).is_empty());
+
+        let page = |page, per_page| PageQuery {
+            page: Some(page),
+            per_page: Some(per_page),
+        };
+        assert!(invalid(&page(1, PER_PAGE_MAX)).is_empty());
+        assert!(invalid(&page(PAGE_MAX, 1)).is_empty());
+        assert_eq!(invalid(&page(0, 1)), ["page"]);
+        assert_eq!(invalid(&page(PAGE_MAX + 1, 1)), ["page"]);
+        assert_eq!(invalid(&page(i64::MAX, PER_PAGE_MAX)), ["page"]);
+        assert_eq!(invalid(&page(1, 0)), ["per_page"]);
+        assert_eq!(invalid(&page(-1, PER_PAGE_MAX + 1)), ["page", "per_page"]);
+    }
+
+    #[test]
+    fn comments_must_be_set_and_short() {
+        let comment = |body: String| CommentData { body };
+
+        assert!(invalid(&comment("Done on staging".to_string())).is_empty());
+        assert_eq!(invalid(&comment(" \n ".to_string())), ["body"]);
+        let too_long = "a".repeat(COMMENT_BODY_MAX_LENGTH + 1);
+        assert_eq!(invalid(&comment(too_long)), ["body"]);
+    }
+
+    #[test]
+    fn report_ranges_are_ordered_and_bounded() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let range = |days| DateRangeQuery {
+            from,
+            to: from + chrono::Duration::days(days),
+        };
+
+        assert!(invalid(&range(0)).is_empty());
+        assert!(invalid(&range(REPORT_DAYS_MAX - 1)).is_empty());
+        assert_eq!(invalid(&range(REPORT_DAYS_MAX)), ["to"]);
+        assert_eq!(invalid(&range(-1)), ["to"]);
+    }
+
+    fn custom_field(field_type: CustomFieldType) -> CustomField {
+        CustomField {
+            id: 1,
+            board_id: 1,
+            name: "client".to_string(),
+            field_type,
+            options: vec!["low".to_string(), "high".to_string()],
+            required: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Whether a value is accepted by a field of the type
+    fn accepts(field_type: CustomFieldType, value: serde_json::Value) -> bool {
+        failing(|errors| check_custom_value(&custom_field(field_type), &value, errors)).is_empty()
+    }
+
+    #[test]
+    fn custom_values_match_their_type() {
+        use serde_json::json;
+
+        assert!(accepts(CustomFieldType::Text, json!("ACME")));
+        assert!(!accepts(CustomFieldType::Text, json!(12)));
+        let too_long = "a".repeat(CUSTOM_VALUE_MAX_LENGTH + 1);
+        assert!(!accepts(CustomFieldType::Text, json!(too_long)));
+
+        assert!(accepts(CustomFieldType::Number, json!(1.5)));
+        assert!(!accepts(CustomFieldType::Number, json!("1.5")));
+
+        assert!(accepts(CustomFieldType::Date, json!("2024-02-29")));
+        assert!(!accepts(CustomFieldType::Date, json!("2023-02-29")));
+        assert!(!accepts(CustomFieldType::Date, json!("29/02/2024")));
+
+        assert!(accepts(CustomFieldType::Select, json!("high")));
+        assert!(!accepts(CustomFieldType::Select, json!("HIGH")));
+
+        assert!(accepts(
+            CustomFieldType::Url,
+            json!("https://example.com/a")
+        ));
+        assert!(!accepts(CustomFieldType::Url, json!(true)));
+    }
+
+    #[test]
+    fn custom_value_errors_are_named_after_the_field() {
+        let field = custom_field(CustomFieldType::Number);
+        let errors = failing(|errors| check_custom_value(&field, &serde_json::json!("x"), errors));
+
+        assert_eq!(errors, ["custom.client"]);
+    }
+
+    #[test]
+    fn urls_are_absolute_http_with_a_host() {
+        assert!(is_url("http://example.com"));
+        assert!(is_url("https://example.com:8080/path?query#anchor"));
+
+        assert!(!is_url("example.com"));
+        assert!(!is_url("ftp://example.com"));
+        assert!(!is_url("https://"));
+        assert!(!is_url("https:///path"));
+        assert!(!is_url("https://exa mple.com"));
+        assert!(!is_url(&format!(
+            "https://example.com/{}",
+            "a".repeat(CUSTOM_VALUE_MAX_LENGTH)
+        )));
+    }
+
+    fn workload(group_by: &[&str]) -> WorkloadQuery {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        WorkloadQuery {
+            from,
+            to: from + chrono::Duration::days(30),
+            group_by: group_by
+                .iter()
+                .map(|group| group.parse().unwrap())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn workloads_group_by_one_period_and_one_field() {
+        for group_by in [&[][..], &["week"], &["priority"], &["month", "status"]] {
+            assert!(invalid(&workload(group_by)).is_empty(), "{:?}", group_by);
+        }
+        for group_by in [
+            &["day", "week"][..],
+            &["status", "priority"],
+            &["day", "status", "status"],
+        ] {
+            assert_eq!(invalid(&workload(group_by)), ["group_by"], "{:?}", group_by);
+        }
+    }
+
+    #[test]
+    fn workloads_are_split_by_their_groups() {
+        use crate::domain::WorkloadGroup;
+
+        let query = workload(&["status", "week"]);
+        assert_eq!(query.period(), WorkloadGroup::Week);
+        assert_eq!(query.split(), Some(WorkloadGroup::Status));
+
+        let query = workload(&[]);
+        assert_eq!(query.period(), WorkloadGroup::Day);
+        assert_eq!(query.split(), None);
+    }
+
+    #[test]
+    fn report_ranges_stay_within_the_planned_dates() {
+        let range = DateRangeQuery {
+            from: NaiveDate::MIN,
+            to: NaiveDate::MAX,
+        };
+        assert_eq!(invalid(&range), ["from", "to", "to"]);
+
+        let range = DateRangeQuery {
+            from: max_date(),
+            to: max_date() + chrono::Duration::days(1),
+        };
+        assert_eq!(invalid(&range), ["to"]);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    card::respond,
+    database::DBAccessManager,
+    domain::{Workflow, WorkflowData},
+    validation::Validate,
+};
+
+/// Get the workflow of a board.
+/// Swagger config:
+///   - Operation ID: get_workflow
+///   - HTTP Method: GET
+///   - Path: /workflow
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Response: JSON representation of the ordered columns and allowed transitions
+pub async fn get_workflow(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    match _db_manager.get_workflow(_board_id) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
+
+/// Replace the workflow of a board.
+/// Swagger config:
+///   - Operation ID: replace_workflow
+///   - HTTP Method: PUT
+///   - Path: /workflow
+///   - Path Parameter: board_id (integer, when nested under /boards/{board_id})
+///   - Request Body: WorkflowData (no transitions lets cards move freely)
+///   - Response: JSON representation of the new workflow
+///   - Error 409: a removed column still holds cards
+///   - Error 422: list of the fields that failed validation
+pub async fn replace_workflow(
+    _board_id: i64,
+    _db_manager: DBAccessManager,
+    _new_data: WorkflowData,
+) -> std::result::Result<impl warp::Reply, warp::Rejection> {
+    if let Err(err) = _new_data.validate() {
+        let status = err.to_status_code();
+        return respond::<Workflow>(Err(err), status);
+    }
+
+    match _db_manager.replace_workflow(_board_id, _new_data) {
+        Ok(_branch) => respond(Ok(_branch), warp::http::StatusCode::OK),
+        Err(err) => {
+            let status = err.to_status_code();
+            respond(Err(err), status)
+        }
+    }
+}
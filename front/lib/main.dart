@@ -142,6 +142,26 @@ class _MyHomePageState extends State<MyHomePage> {
     }
   }
 
+  /// Move a card at the end of a column in the database, used when dragging between columns
+  Future<void> moveCard(int id, String status) async {
+    try {
+      var url = Uri.http('localhost:3030', 'api/card/$id/move');
+      var headers = {'Content-Type': 'application/json'};
+      var response = await http.post(url,
+          headers: headers, body: jsonEncode({'status': status}));
+
+      if (response.statusCode == 200) {
+        print('Card moved');
+      } else {
+        // Handle error response
+        print('Request failed with status: ${response.statusCode}.');
+      }
+    } catch (error) {
+      // Handle network or JSON parsing errors
+      print('Error: $error');
+    }
+  }
+
   /// Delete a card from the database and update the UI
   Future<void> deleteCard(id) async {
     try {
@@ -204,7 +224,7 @@ class _MyHomePageState extends State<MyHomePage> {
                 data.status = 'completed';
               }
 
-              updateCardData(data.id, data);
+              moveCard(data.id, data.status);
 
               cards.add(data);
             });
@@ -227,28 +247,130 @@ class _MyHomePageState extends State<MyHomePage> {
 
     // Create the new card if not null
     if (newCard != null) {
-      try {
-        var url = Uri.http('localhost:3030', 'api/card');
-        var headers = {'Content-Type': 'application/json'};
-        var response =
-            await http.post(url, headers: headers, body: newCard.toString());
-
-        if (response.statusCode == 201) {
-          // Card created successfully, update the UI
-          setState(() {
-            cards.add(newCard);
-          });
-        } else {
-          // Handle error response
-          print('Request failed with status: ${response.statusCode}.');
+      await postCard(newCard, false);
+    }
+  }
+
+  /// Send a new card, the server checks the hours planned on its date against the daily capacities
+  /// Under the warn policy the card is created with warnings, under reject the user may overbook
+  Future<void> postCard(CardItem newCard, bool allowOverbook) async {
+    try {
+      var url = Uri.http('localhost:3030', 'api/card',
+          allowOverbook ? {'allow_overbook': 'true'} : null);
+      var headers = {'Content-Type': 'application/json'};
+      var response =
+          await http.post(url, headers: headers, body: newCard.toString());
+
+      if (response.statusCode == 201) {
+        // Card created successfully, update the UI
+        setState(() {
+          cards.add(newCard);
+        });
+        var created = jsonDecode(response.body) as Map<String, dynamic>;
+        var warnings = created['capacity_warnings'] as List<dynamic>?;
+        if (warnings != null) {
+          showCapacityWarning(warnings.join('\n'));
         }
-      } catch (error) {
-        // Handle network or JSON parsing errors
-        print('Error: $error');
+      } else if (response.statusCode == 409) {
+        // Refused by a daily capacity under the reject policy, or by a WIP limit
+        var error = jsonDecode(response.body) as Map<String, dynamic>;
+        var message = error['message'] as String;
+        if (!isCapacityError(error)) {
+          showRefusal(message);
+        } else if (await confirmOverbook(message)) {
+          await postCard(newCard, true);
+        }
+      } else {
+        // Handle error response
+        print('Request failed with status: ${response.statusCode}.');
       }
+    } catch (error) {
+      // Handle network or JSON parsing errors
+      print('Error: $error');
     }
   }
 
+  /// Whether a refusal comes from a daily capacity, the only one overbooking gets past
+  /// Its fields carry the capacity, the planned total and the hours remaining
+  bool isCapacityError(Map<String, dynamic> error) {
+    var fields = (error['fields'] as List<dynamic>? ?? [])
+        .map((field) => (field as Map<String, dynamic>)['field'])
+        .toSet();
+    return fields.containsAll(['capacity', 'total', 'remaining']);
+  }
+
+  /// Show why the server refused a card
+  /// Can be dismissed by clicking on the 'Ok' button, non blocking
+  void showRefusal(String message) {
+    showDialog(
+      context: context,
+      builder: (BuildContext context) {
+        return AlertDialog(
+          title: const Text('Card not created'),
+          content: Text(message),
+          actions: [
+            TextButton(
+              onPressed: () {
+                Navigator.of(context).pop();
+              },
+              child: const Text('Ok'),
+            ),
+          ],
+        );
+      },
+    );
+  }
+
+  /// Show the daily capacities a saved card goes over, as reported by the server
+  /// Can be dismissed by clicking on the 'Ok' button, non blocking
+  void showCapacityWarning(String warning) {
+    showDialog(
+      context: context,
+      builder: (BuildContext context) {
+        return AlertDialog(
+          title: const Text('Capacity warning'),
+          content: Text(warning),
+          actions: [
+            TextButton(
+              onPressed: () {
+                Navigator.of(context).pop();
+              },
+              child: const Text('Ok'),
+            ),
+          ],
+        );
+      },
+    );
+  }
+
+  /// Show why the server refused a card and ask whether to create it over the daily capacity
+  Future<bool> confirmOverbook(String message) async {
+    bool? overbook = await showDialog<bool>(
+      context: context,
+      builder: (BuildContext context) {
+        return AlertDialog(
+          title: const Text('Card not created'),
+          content: Text(message),
+          actions: [
+            TextButton(
+              onPressed: () {
+                Navigator.of(context).pop(false);
+              },
+              child: const Text('Cancel'),
+            ),
+            ElevatedButton(
+              onPressed: () {
+                Navigator.of(context).pop(true);
+              },
+              child: const Text('Create anyway'),
+            ),
+          ],
+        );
+      },
+    );
+    return overbook ?? false;
+  }
+
   /// Builds the lists for the columns and builds them into a table
   @override
   Widget build(BuildContext context) {
@@ -619,6 +741,14 @@ class _NewCardDialogState extends State<NewCardDialog> {
   String selectedStatus = 'todo';
   String selectedPriority = 'high';
   DateTime selectedDate = DateTime.now();
+  // Hours already planned on the selected date, the server warns about capacities on create
+  int plannedHours = 0;
+
+  @override
+  void initState() {
+    super.initState();
+    refreshPlannedHours();
+  }
 
   // Release ressources on dispose, on close
   @override
@@ -645,11 +775,25 @@ class _NewCardDialogState extends State<NewCardDialog> {
         selectedDate = picked;
         dateController.text = DateFormat('yyyy-MM-dd').format(selectedDate);
       });
+      refreshPlannedHours();
+    }
+  }
+
+  // Load the hours planned on the selected date, shown under the date field
+  Future<void> refreshPlannedHours() async {
+    var total = await checkTotalDurationOnDate(
+        DateFormat('yyyy-MM-dd').format(selectedDate));
+    if (mounted) {
+      setState(() {
+        plannedHours = total;
+      });
     }
   }
 
-  // Call the back to check the total duration of work on a given date
+  // Call the back to check the hours of work planned on a given date
   // Calling the back to ensure that it's update to date with the database (maybe multiple users)
+  // The form does not pick assignees, so the new card joins the unassigned ones:
+  // the busiest of the assignees and the unassigned hours on that date is checked
   Future<int> checkTotalDurationOnDate(String date) async {
     try {
       var url = Uri.http('localhost:3030', 'api/card/check/$date');
@@ -658,7 +802,13 @@ class _NewCardDialogState extends State<NewCardDialog> {
       );
 
       if (response.statusCode == 200) {
-        return response.body as int;
+        var planned = jsonDecode(response.body) as Map<String, dynamic>;
+        var assignees = planned['assignees'] as List<dynamic>;
+        return assignees.fold<int>(
+            planned['unassigned'] as int,
+            (busiest, assignee) => (assignee['duration'] as int) > busiest
+                ? assignee['duration'] as int
+                : busiest);
       } else {
         // Handle error response
         print('Request failed with status: ${response.statusCode}.');
@@ -671,30 +821,6 @@ class _NewCardDialogState extends State<NewCardDialog> {
     }
   }
 
-  // Show alert dialog when the user is trying to put more than 8 hours of work on a date
-  // Can be dismissed by clicking on the 'Ok' button and either changing the date or the duration or keeping it the same
-  // Non blocking
-  void showAlertMaxTime() {
-    showDialog(
-      context: context,
-      builder: (BuildContext context) {
-        return AlertDialog(
-          title: const Text('Error'),
-          content: const Text(
-              'Be careful, you are putting more than 8 hours of work on this date'),
-          actions: [
-            TextButton(
-              onPressed: () {
-                Navigator.of(context).pop();
-              },
-              child: const Text('Ok'),
-            ),
-          ],
-        );
-      },
-    );
-  }
-
   // Build the new card dialog
   // TODO: Add validation to the form
   @override
@@ -726,17 +852,10 @@ class _NewCardDialogState extends State<NewCardDialog> {
               child: IgnorePointer(
                 child: TextField(
                   controller: dateController,
-                  onChanged: (value) async {
-                    if (durationController.text.isEmpty) return;
-
-                    var date = value.toString();
-                    var total = await checkTotalDurationOnDate(date);
-                    var time = int.tryParse(durationController.text) ?? 0;
-                    if (total + time > 8) {
-                      showAlertMaxTime();
-                    }
-                  },
-                  decoration: const InputDecoration(labelText: 'Date'),
+                  decoration: InputDecoration(
+                    labelText: 'Date',
+                    helperText: '$plannedHours hour(s) already planned',
+                  ),
                 ),
               ),
             ),
@@ -790,15 +909,6 @@ class _NewCardDialogState extends State<NewCardDialog> {
                 controller: durationController,
                 decoration: const InputDecoration(labelText: 'Duration'),
                 keyboardType: TextInputType.number,
-                onChanged: (value) async {
-                  var date = DateFormat('yyyy-MM-dd').format(selectedDate);
-                  var total = await checkTotalDurationOnDate(date);
-                  var time = int.tryParse(value) ?? 0;
-                  if (total + time > 8) {
-                    // Alert if the time is more than 8 hours
-                    showAlertMaxTime();
-                  }
-                },
                 inputFormatters: <TextInputFormatter>[
                   FilteringTextInputFormatter.digitsOnly
                 ]),